@@ -1,12 +1,20 @@
+#![allow(clippy::needless_return)]
+
 use blog_tools::{
+    feed::{create_atom_feed, create_json_feed, create_rss_feed, create_tag_rss_feed, FeedOptions},
     low::{
         get_blog_tag_list, preview_blogs, preview_blogs_tagged, render_blog_post, PreviewBlogEntry,
     },
     sitemap::{create_sitemap, SitemapOptions},
+    tags::Tag,
+    BlogOptions,
 };
 use rocket::{
     fs::{relative, FileServer},
-    response::{content::RawXml, Redirect},
+    response::{
+        content::{RawJson, RawXml},
+        Redirect,
+    },
     Request, Route,
 };
 use rocket_dyn_templates::Template;
@@ -46,13 +54,23 @@ fn blog_index() -> Option<Template> {
     #[derive(Serialize, Deserialize)]
     struct Blogs {
         entries: Vec<PreviewBlogEntry>,
-        tags: Vec<String>,
+        tags: Vec<Tag>,
     }
 
     let mut context = rocket_dyn_templates::tera::Context::new();
 
-    let preview = preview_blogs(PathBuf::from_str(BLOG_ROOT).unwrap(), 2, None).unwrap();
-    let tags = get_blog_tag_list(PathBuf::from_str(BLOG_ROOT).unwrap()).unwrap();
+    let preview = preview_blogs(
+        PathBuf::from_str(BLOG_ROOT).unwrap(),
+        2,
+        None,
+        &BlogOptions::default(),
+    )
+    .unwrap();
+    let tags = get_blog_tag_list(
+        PathBuf::from_str(BLOG_ROOT).unwrap(),
+        &BlogOptions::default(),
+    )
+    .unwrap();
     context.insert(
         "blog",
         &Blogs {
@@ -66,8 +84,14 @@ fn blog_index() -> Option<Template> {
 #[get("/blog/<date>/<slug>", rank = 2)]
 fn blog_article(date: String, slug: String) -> Option<Template> {
     let mut context = rocket_dyn_templates::tera::Context::new();
-    let blog_post =
-        render_blog_post(PathBuf::from_str(BLOG_ROOT).unwrap(), date, slug, None).unwrap();
+    let blog_post = render_blog_post(
+        PathBuf::from_str(BLOG_ROOT).unwrap(),
+        date,
+        slug,
+        None,
+        &BlogOptions::default(),
+    )
+    .unwrap();
 
     context.insert("blog", &blog_post);
     Some(Template::render("blog", context.into_json()))
@@ -77,8 +101,13 @@ fn blog_article(date: String, slug: String) -> Option<Template> {
 fn tag_page(slug: String) -> Option<Template> {
     let mut context = rocket_dyn_templates::tera::Context::new();
     context.insert("tag", &slug);
-    let all_blogs =
-        preview_blogs_tagged(PathBuf::from_str(BLOG_ROOT).unwrap(), slug, None).unwrap();
+    let all_blogs = preview_blogs_tagged(
+        PathBuf::from_str(BLOG_ROOT).unwrap(),
+        slug,
+        None,
+        &BlogOptions::default(),
+    )
+    .unwrap();
 
     context.insert("blogs", &all_blogs);
 
@@ -98,11 +127,81 @@ fn sitemap() -> RawXml<String> {
             sitemap_base: Some(base_sitemap),
             ..Default::default()
         },
+        &BlogOptions::default(),
     );
 
     return RawXml(sitemap.unwrap());
 }
 
+#[get("/blog/feed.xml")]
+fn feed() -> RawXml<String> {
+    let feed = create_rss_feed(
+        BLOG_ROOT,
+        &URL.to_string(),
+        &SitemapOptions::default(),
+        &FeedOptions {
+            title: "Example Blog".to_string(),
+            description: "An example blog".to_string(),
+            ..Default::default()
+        },
+        &BlogOptions::default(),
+    );
+
+    return RawXml(feed.unwrap());
+}
+
+#[get("/blog/atom.xml")]
+fn atom_feed() -> RawXml<String> {
+    let feed = create_atom_feed(
+        BLOG_ROOT,
+        &URL.to_string(),
+        &SitemapOptions::default(),
+        &FeedOptions {
+            title: "Example Blog".to_string(),
+            description: "An example blog".to_string(),
+            ..Default::default()
+        },
+        &BlogOptions::default(),
+    );
+
+    return RawXml(feed.unwrap());
+}
+
+#[get("/blog/feed.json")]
+fn json_feed() -> RawJson<String> {
+    let feed = create_json_feed(
+        BLOG_ROOT,
+        &URL.to_string(),
+        &SitemapOptions::default(),
+        &FeedOptions {
+            title: "Example Blog".to_string(),
+            description: "An example blog".to_string(),
+            ..Default::default()
+        },
+        &BlogOptions::default(),
+    );
+
+    return RawJson(feed.unwrap());
+}
+
+#[get("/blog/tag/<slug>/feed.xml")]
+fn tag_feed(slug: String) -> RawXml<String> {
+    let feed = create_tag_rss_feed(
+        BLOG_ROOT,
+        &slug,
+        &URL.to_string(),
+        &SitemapOptions::default(),
+        &FeedOptions {
+            title: "Example Blog".to_string(),
+            description: "An example blog".to_string(),
+            ..Default::default()
+        },
+        &BlogOptions::default(),
+    );
+
+    return RawXml(feed.unwrap());
+}
+
 #[catch(404)]
 async fn not_found(req: &Request<'_>) -> Redirect {
     let mut context = rocket_dyn_templates::tera::Context::new();
@@ -118,5 +217,14 @@ async fn error(req: &Request<'_>) -> Redirect {
 }
 
 fn get_all_routes() -> Vec<Route> {
-    return routes![blog_index, blog_article, tag_page, sitemap];
+    return routes![
+        blog_index,
+        blog_article,
+        tag_page,
+        sitemap,
+        feed,
+        atom_feed,
+        json_feed,
+        tag_feed
+    ];
 }
@@ -1,14 +1,20 @@
+#![allow(clippy::needless_return)]
+
 use std::{fs, path::PathBuf, str::FromStr};
 
 use blog_tools::{
+    feed::FeedOptions,
     medium::{get_medium_blog, MediumBlog, MediumBlogEntry},
     sitemap::SitemapOptions,
-    Blog,
+    Blog, BlogOptions,
 };
 use lazy_static::lazy_static;
 use rocket::{
     fs::{relative, FileServer},
-    response::{content::RawXml, Redirect},
+    response::{
+        content::{RawJson, RawXml},
+        Redirect,
+    },
     Request, Route,
 };
 use rocket_dyn_templates::Template;
@@ -45,6 +51,27 @@ fn sitemap() -> RawXml<String> {
     return RawXml(blog.sitemap.clone());
 }
 
+#[get("/blog/feed.xml")]
+fn feed() -> RawXml<String> {
+    let blog = get_blog_context();
+
+    return RawXml(blog.feed.clone());
+}
+
+#[get("/blog/atom.xml")]
+fn atom_feed() -> RawXml<String> {
+    let blog = get_blog_context();
+
+    return RawXml(blog.atom_feed.clone());
+}
+
+#[get("/blog/feed.json")]
+fn json_feed() -> RawJson<String> {
+    let blog = get_blog_context();
+
+    return RawJson(blog.json_feed.clone());
+}
+
 #[get("/blog")]
 fn blog_index() -> Option<Template> {
     let mut context = rocket_dyn_templates::tera::Context::new();
@@ -52,20 +79,27 @@ fn blog_index() -> Option<Template> {
     Some(Template::render("blog_index", context.into_json()))
 }
 
+#[get("/blog/tag/<slug>/feed.xml")]
+fn tag_feed(slug: String) -> Option<RawXml<String>> {
+    let blog = get_blog_context();
+
+    return blog.tag_feeds.get(&slug).map(|x| RawXml(x.clone()));
+}
+
 #[get("/blog/<date>/<slug>", rank = 2)]
 fn blog_article(date: String, slug: String) -> Option<Template> {
     let mut context = rocket_dyn_templates::tera::Context::new();
     let all_blogs = get_blog_context();
     let complete_slug = format!("{}/{}", date, slug);
-    let this_blog = match all_blogs.hash.get(&complete_slug) {
-        Some(x) => x,
-        None => return None,
-    };
+    let this_blog = all_blogs.hash.get(&complete_slug)?;
 
     context.insert(
         "blog",
         &this_blog
-            .render(PathBuf::from_str(BLOG_ROOT).unwrap())
+            .render(
+                PathBuf::from_str(BLOG_ROOT).unwrap(),
+                &BlogOptions::default(),
+            )
             .unwrap(),
     );
     Some(Template::render("blog", context.into_json()))
@@ -79,8 +113,8 @@ fn tag_page(slug: String) -> Option<Template> {
     let mut these_blogs: Vec<&MediumBlogEntry> = vec![];
 
     for blog in &all_blogs.entries {
-        if blog.get_tags().contains(&slug) {
-            these_blogs.push(&blog);
+        if blog.get_tag_slugs().contains(&slug) {
+            these_blogs.push(blog);
         }
     }
 
@@ -104,7 +138,16 @@ async fn error(req: &Request<'_>) -> Redirect {
 }
 
 fn get_all_routes() -> Vec<Route> {
-    return routes![blog_index, blog_article, tag_page, sitemap];
+    return routes![
+        blog_index,
+        blog_article,
+        tag_page,
+        sitemap,
+        feed,
+        atom_feed,
+        json_feed,
+        tag_feed
+    ];
 }
 
 pub static BLOG_ROOT: &str = "examples/blog";
@@ -120,7 +163,14 @@ lazy_static! {
             include_tags: true,
             sitemap_base: Some(fs::read_to_string("examples/xml/sitemap.xml").unwrap()),
             ..Default::default()
-        }
+        },
+        &FeedOptions {
+            title: "Example Blog".to_string(),
+            description: "An example blog".to_string(),
+            include_tag_feeds: true,
+            ..Default::default()
+        },
+        &BlogOptions::default()
     )
     .unwrap();
 }
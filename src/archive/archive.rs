@@ -0,0 +1,86 @@
+use std::cmp::Reverse;
+
+use chrono::{Datelike, Month};
+
+use crate::types::Blog;
+
+use super::types::{ArchiveMonth, ArchiveYear};
+
+/// Groups the blogs by year and then by month, newest first
+pub fn build_archive<T: Blog>(entries: &[T]) -> Vec<ArchiveYear> {
+    let mut sorted: Vec<&T> = entries.iter().collect();
+    sorted.sort_by_key(|x| Reverse(x.get_date_listed()));
+
+    let mut archive: Vec<ArchiveYear> = vec![];
+
+    for entry in sorted {
+        let date = entry.get_date_listed();
+
+        add_blog(&mut archive, date.year(), date.month())
+            .slugs
+            .push(entry.get_full_slug());
+    }
+
+    return archive;
+}
+
+/// Groups the year and month of each blog, newest first, counting the blogs
+/// without knowing their slugs
+pub fn count_archive(mut months: Vec<(i32, u32)>) -> Vec<ArchiveYear> {
+    months.sort_by(|a, b| b.cmp(a));
+
+    let mut archive: Vec<ArchiveYear> = vec![];
+
+    for (year, month) in months {
+        add_blog(&mut archive, year, month);
+    }
+
+    return archive;
+}
+
+/// The URL of the archive page for a year, or a month of a year, without the
+/// leading `/` e.g. `blog/archive/2024/03`
+pub fn archive_url(root_slug: &str, year: i32, month: Option<u32>) -> String {
+    return match month {
+        Some(x) => format!("{}/{}/{:02}", root_slug, year, x),
+        None => format!("{}/{}", root_slug, year),
+    };
+}
+
+// Counts a blog in its year and month, adding these to the archive if they
+// aren't there yet. Blogs are added newest first, so a new year or month
+// always goes at the end
+fn add_blog(archive: &mut Vec<ArchiveYear>, year: i32, month: u32) -> &mut ArchiveMonth {
+    if archive.last().map(|x| x.year) != Some(year) {
+        archive.push(ArchiveYear {
+            year: year,
+            count: 0,
+            months: vec![],
+        });
+    }
+
+    let archive_year = archive.last_mut().unwrap();
+    archive_year.count += 1;
+
+    if archive_year.months.last().map(|x| x.month) != Some(month) {
+        archive_year.months.push(ArchiveMonth {
+            year: year,
+            month: month,
+            name: month_name(month),
+            count: 0,
+            slugs: vec![],
+        });
+    }
+
+    let archive_month = archive_year.months.last_mut().unwrap();
+    archive_month.count += 1;
+
+    return archive_month;
+}
+
+fn month_name(month: u32) -> String {
+    return match Month::try_from(month as u8) {
+        Ok(x) => x.name().to_string(),
+        Err(_) => String::new(),
+    };
+}
@@ -0,0 +1,5 @@
+mod archive;
+mod types;
+pub use types::{ArchiveMonth, ArchiveYear};
+
+pub(crate) use archive::{archive_url, build_archive, count_archive};
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// The blogs published in a single year, grouped by month
+///
+/// When this comes from `low::get_archive` with `from_paths` set, the counts
+/// include drafts and scheduled blogs, as the blogs are never read
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArchiveYear {
+    /// The year e.g. `2024`
+    pub year: i32,
+    /// The number of blogs published in the year
+    pub count: usize,
+    /// Every month of the year with at least one blog, newest first
+    pub months: Vec<ArchiveMonth>,
+}
+
+/// The blogs published in a single month
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArchiveMonth {
+    /// The year e.g. `2024`
+    pub year: i32,
+    /// The month, from 1 for January to 12 for December
+    pub month: u32,
+    /// The name of the month e.g. `March`
+    pub name: String,
+    /// The number of blogs published in the month
+    pub count: usize,
+    /// The full slugs of the blogs published in the month, newest first. Empty
+    /// when the archive comes from `low::get_archive`
+    pub slugs: Vec<String>,
+}
@@ -0,0 +1,350 @@
+use std::{collections::HashMap, ffi::OsStr, fs, path::Path};
+
+use chrono::NaiveDate;
+use serde_json::Value;
+use walkdir::WalkDir;
+
+use crate::{
+    common::{
+        front_matter::{front_matter_to_json, split_front_matter},
+        get_json_path, is_blog_file, parse_publish_at, BlogJson,
+    },
+    types::BlogOptions,
+};
+
+use super::types::{CheckReport, ProblemKind};
+
+/// Checks every blog underneath `base`, returning a report of every problem
+/// found rather than stopping at the first one. Useful in CI, or before
+/// deploying.
+///
+/// Drafts and scheduled blogs are checked as well, regardless of
+/// `include_drafts`. Every JSON file underneath `base` with a `slug` and a
+/// `date` is expected to be the sidecar of a blog. Other JSON files, such as
+/// the tag aliases, are left alone
+///
+/// ```rust,ignore
+/// let report = check_blog(BLOG_ROOT, &BlogOptions::default());
+///
+/// if !report.is_ok() {
+///     eprint!("{}", report);
+///     std::process::exit(1);
+/// }
+/// ```
+pub fn check_blog<T: AsRef<Path>>(base: T, blog_options: &BlogOptions) -> CheckReport {
+    let base = base.as_ref();
+    let mut report = CheckReport { problems: vec![] };
+
+    if !base.is_dir() {
+        report.push(base, ProblemKind::NotADirectory);
+        return report;
+    }
+
+    let blog_paths = match blog_options.layout.blog_paths(base) {
+        Ok(x) => x,
+        Err(y) => {
+            report.push(base, ProblemKind::Unreadable(y.to_string()));
+            return report;
+        }
+    };
+
+    let mut slugs: HashMap<String, &Path> = HashMap::new();
+
+    for path in &blog_paths {
+        let json = match check_metadata(path, &mut report) {
+            Some(x) => x,
+            None => continue,
+        };
+
+        if let Some(priority) = json.priority {
+            if !(0.0..=1.0).contains(&priority) {
+                report.push(path, ProblemKind::ImproperPriority(priority));
+            }
+        }
+
+        let full_slug = format!("{}/{}", json.date, json.slug);
+
+        match slugs.get(&full_slug) {
+            Some(&other) => report.push(
+                path,
+                ProblemKind::DuplicateSlug {
+                    slug: full_slug.clone(),
+                    other: other.to_path_buf(),
+                },
+            ),
+            None => {
+                slugs.insert(full_slug.clone(), path);
+            }
+        }
+
+        match blog_options
+            .layout
+            .candidate_paths(base, json.date, &json.slug)
+        {
+            Ok(x) => {
+                if !x.contains(path) {
+                    report.push(path, ProblemKind::NotFoundByLayout { slug: full_slug });
+                }
+            }
+            Err(y) => report.push(path, ProblemKind::Unreadable(y.to_string())),
+        }
+    }
+
+    check_orphaned_json(base, &mut report);
+
+    return report;
+}
+
+// Reads the metadata of a blog, recording any problems. Returns `None` if the
+// metadata could not be read
+fn check_metadata(path: &Path, report: &mut CheckReport) -> Option<BlogJson> {
+    let text = match fs::read_to_string(path) {
+        Ok(x) => x,
+        Err(y) => {
+            report.push(path, ProblemKind::Unreadable(y.to_string()));
+            return None;
+        }
+    };
+
+    if let Some((front_matter, _)) = split_front_matter(&text) {
+        let value = match front_matter_to_json(&front_matter) {
+            Ok(x) => x,
+            Err(y) => {
+                report.push(
+                    path,
+                    ProblemKind::MalformedFrontMatter {
+                        line: y.line,
+                        column: y.column,
+                        message: y.message,
+                    },
+                );
+                return None;
+            }
+        };
+
+        if !check_dates(path, &value, report) {
+            return None;
+        }
+
+        return match serde_json::from_value(value) {
+            Ok(x) => Some(x),
+            Err(y) => {
+                report.push(
+                    path,
+                    ProblemKind::MalformedFrontMatter {
+                        line: None,
+                        column: None,
+                        message: y.to_string(),
+                    },
+                );
+                None
+            }
+        };
+    }
+
+    let json_path = match get_json_path(path) {
+        Ok(x) => x,
+        Err(y) => {
+            report.push(path, ProblemKind::Unreadable(y.to_string()));
+            return None;
+        }
+    };
+
+    if !json_path.is_file() {
+        report.push(path, ProblemKind::MissingJson);
+        return None;
+    }
+
+    let json_text = match fs::read_to_string(&json_path) {
+        Ok(x) => x,
+        Err(y) => {
+            report.push(json_path, ProblemKind::Unreadable(y.to_string()));
+            return None;
+        }
+    };
+
+    // Parsing into a plain value first means bad dates can be reported as
+    // such, rather than as a generic parsing error
+    let value: Value = match serde_json::from_str(&json_text) {
+        Ok(x) => x,
+        Err(y) => {
+            report.push(json_path, malformed_json(&y));
+            return None;
+        }
+    };
+
+    if !check_dates(&json_path, &value, report) {
+        return None;
+    }
+
+    return match serde_json::from_str(&json_text) {
+        Ok(x) => Some(x),
+        Err(y) => {
+            report.push(json_path, malformed_json(&y));
+            None
+        }
+    };
+}
+
+// Records any date fields which can't be parsed. Returns `true` if every date
+// is fine
+fn check_dates(path: &Path, value: &Value, report: &mut CheckReport) -> bool {
+    let mut ok = true;
+
+    for field in ["date", "last_modified", "publish_at"] {
+        let text = match value.get(field) {
+            Some(Value::String(x)) => x,
+            _ => continue,
+        };
+
+        let valid = match field {
+            "publish_at" => parse_publish_at(text).is_some(),
+            _ => text.parse::<NaiveDate>().is_ok(),
+        };
+
+        if !valid {
+            report.push(
+                path,
+                ProblemKind::ImproperDate {
+                    field: field.to_string(),
+                    value: text.clone(),
+                },
+            );
+            ok = false;
+        }
+    }
+
+    return ok;
+}
+
+// Records any sidecar JSON files which do not have markdown next to them
+fn check_orphaned_json(base: &Path, report: &mut CheckReport) {
+    for entry in WalkDir::new(base) {
+        let entry = match entry {
+            Ok(x) => x,
+            Err(y) => {
+                let path = match y.path() {
+                    Some(x) => x.to_path_buf(),
+                    None => base.to_path_buf(),
+                };
+                report.push(path, ProblemKind::Unreadable(y.to_string()));
+                continue;
+            }
+        };
+
+        let path = entry.path();
+
+        if path.extension() != Some(OsStr::new("json")) || !is_sidecar(path) {
+            continue;
+        }
+
+        let parent = match path.parent() {
+            Some(x) => x,
+            None => continue,
+        };
+
+        // The same blogs and sidecars as when the blog is read
+        let has_markdown = match fs::read_dir(parent) {
+            Ok(x) => x.filter_map(|e| e.ok()).any(|e| {
+                let other = e.path();
+                is_blog_file(&other)
+                    && match get_json_path(&other) {
+                        Ok(y) => y == path,
+                        Err(_) => false,
+                    }
+            }),
+            Err(_) => false,
+        };
+
+        if !has_markdown {
+            report.push(entry.path(), ProblemKind::MissingMarkdown);
+        }
+    }
+}
+
+// Whether the JSON file looks like the metadata of a blog, rather than some
+// other JSON such as the tag aliases
+fn is_sidecar(path: &Path) -> bool {
+    let text = match fs::read_to_string(path) {
+        Ok(x) => x,
+        Err(_) => return false,
+    };
+
+    return match serde_json::from_str::<Value>(&text) {
+        Ok(Value::Object(x)) => x.contains_key("slug") && x.contains_key("date"),
+        _ => false,
+    };
+}
+
+fn malformed_json(error: &serde_json::Error) -> ProblemKind {
+    // The message would otherwise repeat the line and column
+    let message = error.to_string();
+    let suffix = format!(" at line {} column {}", error.line(), error.column());
+
+    return ProblemKind::MalformedJson {
+        line: error.line(),
+        column: error.column(),
+        message: match message.strip_suffix(&suffix) {
+            Some(x) => x.to_string(),
+            None => message.clone(),
+        },
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_blog;
+    use crate::{
+        common::get_blog_paths, layout::FlatLayout, testing::TempBlog, types::BlogOptions,
+    };
+
+    #[test]
+    fn only_reports_sidecars_of_no_blog() {
+        let base = TempBlog::new();
+
+        let sidecar = |slug: &str| {
+            format!(
+                r#"{{"title": "A", "date": "2024-01-02", "slug": "{}", "tags": []}}"#,
+                slug
+            )
+        };
+
+        base.write("good.json", &sidecar("good"));
+        base.write("good.md", "Hello");
+        // The sidecar is named after the part of the file name before the
+        // first `.`
+        base.write("post.json", &sidecar("post"));
+        base.write("post.en.md", "Hello");
+        // Neither of these is a blog
+        base.write("backup.json", &sidecar("backup"));
+        base.write("backup.md.bak", "Hello");
+        base.write("other.json", &sidecar("other"));
+        base.write("other.mdx", "Hello");
+        // Not a sidecar at all
+        base.write("tags.json", r#"{"Machine Learning": ["ML"]}"#);
+
+        let mut blogs: Vec<String> = get_blog_paths(&base, Some(1))
+            .unwrap()
+            .iter()
+            .map(|x| x.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        blogs.sort();
+
+        assert_eq!(blogs, vec!["good.md", "post.en.md"]);
+
+        let blog_options = BlogOptions {
+            layout: Box::new(FlatLayout),
+            ..BlogOptions::default()
+        };
+        let report = check_blog(&base, &blog_options);
+
+        let mut orphans: Vec<String> = report
+            .problems
+            .iter()
+            .map(|x| x.path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        orphans.sort();
+
+        assert_eq!(orphans, vec!["backup.json", "other.json"]);
+    }
+}
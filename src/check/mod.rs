@@ -0,0 +1,4 @@
+mod check;
+mod types;
+pub use check::check_blog;
+pub use types::{CheckReport, Problem, ProblemKind};
@@ -0,0 +1,147 @@
+use std::{fmt::Display, path::PathBuf};
+
+use serde::Serialize;
+
+/// Every problem found while checking a blog
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckReport {
+    /// Every problem, in the order they were found
+    pub problems: Vec<Problem>,
+}
+
+impl CheckReport {
+    /// `true` if no problems were found
+    pub fn is_ok(&self) -> bool {
+        return self.problems.is_empty();
+    }
+
+    pub(crate) fn push<T: Into<PathBuf>>(&mut self, path: T, kind: ProblemKind) {
+        self.problems.push(Problem {
+            path: path.into(),
+            kind: kind,
+        });
+    }
+}
+
+/// Lists every problem, one per line
+impl Display for CheckReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for problem in &self.problems {
+            writeln!(f, "{}", problem)?;
+        }
+        return Ok(());
+    }
+}
+
+/// A single problem, along with the file it was found in
+#[derive(Debug, Clone, Serialize)]
+pub struct Problem {
+    /// The file the problem was found in
+    pub path: PathBuf,
+    /// What is wrong with the file
+    pub kind: ProblemKind,
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{}: {}", self.path.display(), self.kind);
+    }
+}
+
+/// The kinds of problem `check_blog` can find
+#[derive(Debug, Clone, Serialize)]
+pub enum ProblemKind {
+    /// The blog root is not a directory
+    NotADirectory,
+    /// A file or directory could not be read, with the reason
+    Unreadable(String),
+    /// A sidecar JSON could not be parsed. Line and column are one based
+    MalformedJson {
+        /// The line of the JSON the problem was found on
+        line: usize,
+        /// The column of the JSON the problem was found on
+        column: usize,
+        /// The reason the JSON could not be parsed
+        message: String,
+    },
+    /// Front matter could not be parsed. Line and column are one based and
+    /// counted from the top of the markdown, where they are known
+    MalformedFrontMatter {
+        /// The line of the markdown the problem was found on
+        line: Option<usize>,
+        /// The column of the markdown the problem was found on
+        column: Option<usize>,
+        /// The reason the front matter could not be parsed
+        message: String,
+    },
+    /// A markdown file has no front matter and no sidecar JSON
+    MissingJson,
+    /// A JSON file has no markdown next to it
+    MissingMarkdown,
+    /// A date field which is not a valid date
+    ImproperDate {
+        /// The name of the field
+        field: String,
+        /// The date as found
+        value: String,
+    },
+    /// A sitemap priority outside of 0.0 to 1.0
+    ImproperPriority(f64),
+    /// Another blog already has this full slug
+    DuplicateSlug {
+        /// The full slug, including the date
+        slug: String,
+        /// The other blog with this slug
+        other: PathBuf,
+    },
+    /// The layout would not find this blog from its date and slug, so it could
+    /// not be rendered by `medium` or `low`
+    NotFoundByLayout {
+        /// The full slug, including the date
+        slug: String,
+    },
+}
+
+impl Display for ProblemKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProblemKind::NotADirectory => write!(f, "not a directory"),
+            ProblemKind::Unreadable(a) => write!(f, "could not be read: {}", a),
+            ProblemKind::MalformedJson {
+                line,
+                column,
+                message,
+            } => write!(f, "malformed JSON at {}:{}: {}", line, column, message),
+            ProblemKind::MalformedFrontMatter {
+                line: Some(line),
+                column: Some(column),
+                message,
+            } => write!(
+                f,
+                "malformed front matter at {}:{}: {}",
+                line, column, message
+            ),
+            ProblemKind::MalformedFrontMatter { message, .. } => {
+                write!(f, "malformed front matter: {}", message)
+            }
+            ProblemKind::MissingJson => write!(f, "no front matter and no JSON next to it"),
+            ProblemKind::MissingMarkdown => write!(f, "no markdown next to it"),
+            ProblemKind::ImproperDate { field, value } => write!(
+                f,
+                "`{}` is `{}` which is not a valid date - dates should be in the yyyy-mm-dd format",
+                field, value
+            ),
+            ProblemKind::ImproperPriority(b) => {
+                write!(f, "priority {} is not between 0.0 and 1.0", b)
+            }
+            ProblemKind::DuplicateSlug { slug, other } => {
+                write!(f, "slug `{}` is also used by {}", slug, other.display())
+            }
+            ProblemKind::NotFoundByLayout { slug } => write!(
+                f,
+                "the layout would not find this blog at `{}` - check it is in the right folder",
+                slug
+            ),
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use serde_json::{Map, Number, Value};
+
+/// The kind of front matter found at the top of a markdown file
+pub enum FrontMatter<'a> {
+    /// YAML front matter, fenced by `---`
+    Yaml(&'a str),
+    /// TOML front matter, fenced by `+++`
+    Toml(&'a str),
+}
+
+/// Splits the front matter from the top of a markdown file. Returns `None` if
+/// the file does not start with a `---` or `+++` fence, otherwise returns the
+/// front matter and the remaining markdown body
+pub fn split_front_matter(text: &str) -> Option<(FrontMatter<'_>, &str)> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+
+    let (first_line, rest) = text.split_once('\n')?;
+
+    let fence = first_line.trim_end();
+
+    if fence != "---" && fence != "+++" {
+        return None;
+    }
+
+    let mut offset = 0;
+
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == fence {
+            let matter = &rest[..offset];
+            let body = &rest[offset + line.len()..];
+
+            return if fence == "---" {
+                Some((FrontMatter::Yaml(matter), body))
+            } else {
+                Some((FrontMatter::Toml(matter), body))
+            };
+        }
+
+        offset += line.len();
+    }
+
+    return None;
+}
+
+/// Front matter which could not be parsed. The line and column are counted
+/// from the top of the markdown file, where they are known
+pub struct FrontMatterError {
+    pub message: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+/// Parses front matter into a JSON value so it can be deserialized in exactly
+/// the same way as a sidecar JSON file
+pub fn front_matter_to_json(front_matter: &FrontMatter) -> Result<Value, FrontMatterError> {
+    return match front_matter {
+        FrontMatter::Yaml(x) => match serde_yaml::from_str(x) {
+            Ok(y) => Ok(y),
+            Err(z) => {
+                let location = z.location();
+                // The message would otherwise repeat the location, counted
+                // from the top of the front matter rather than the file
+                let message = match &location {
+                    Some(l) => z.to_string().replacen(
+                        &format!(" at line {} column {}", l.line(), l.column()),
+                        "",
+                        1,
+                    ),
+                    None => z.to_string(),
+                };
+                Err(FrontMatterError {
+                    message: message,
+                    // The front matter starts on the line after the fence
+                    line: location.as_ref().map(|l| l.line() + 1),
+                    column: location.as_ref().map(|l| l.column()),
+                })
+            }
+        },
+        FrontMatter::Toml(x) => match x.parse::<toml::Table>() {
+            Ok(y) => Ok(toml_to_json(toml::Value::Table(y))),
+            Err(z) => {
+                let position = z.span().map(|s| line_and_column(x, s.start));
+                Err(FrontMatterError {
+                    message: z.message().trim().replace('\n', ", "),
+                    line: position.map(|p| p.0 + 1),
+                    column: position.map(|p| p.1),
+                })
+            }
+        },
+    };
+}
+
+// Converts a byte offset into a one based line and column
+fn line_and_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+
+    let line = before.matches('\n').count() + 1;
+    let column = match before.rfind('\n') {
+        Some(x) => before[x + 1..].chars().count() + 1,
+        None => before.chars().count() + 1,
+    };
+
+    return (line, column);
+}
+
+// TOML has a native date type, which would otherwise not deserialize into a
+// `NaiveDate`, so everything is converted into plain JSON first
+fn toml_to_json(value: toml::Value) -> Value {
+    return match value {
+        toml::Value::String(x) => Value::String(x),
+        toml::Value::Integer(x) => Value::Number(x.into()),
+        toml::Value::Float(x) => match Number::from_f64(x) {
+            Some(y) => Value::Number(y),
+            None => Value::Null,
+        },
+        toml::Value::Boolean(x) => Value::Bool(x),
+        toml::Value::Datetime(x) => Value::String(x.to_string()),
+        toml::Value::Array(x) => Value::Array(x.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(x) => {
+            let mut map = Map::new();
+            for (k, v) in x {
+                map.insert(k, toml_to_json(v));
+            }
+            Value::Object(map)
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::{front_matter_to_json, split_front_matter, FrontMatter};
+
+    #[test]
+    fn splits_yaml_and_toml() {
+        let (matter, body) = split_front_matter("---\ntitle: A\n---\nHello").unwrap();
+        assert!(matches!(matter, FrontMatter::Yaml("title: A\n")));
+        assert_eq!(body, "Hello");
+
+        let (matter, body) = split_front_matter("+++\ntitle = \"A\"\n+++\nHello").unwrap();
+        assert!(matches!(matter, FrontMatter::Toml("title = \"A\"\n")));
+        assert_eq!(body, "Hello");
+
+        assert!(split_front_matter("Hello\n---\n").is_none());
+        assert!(split_front_matter("---\ntitle: A\n").is_none());
+    }
+
+    #[test]
+    fn toml_dates_become_strings() {
+        let value = match front_matter_to_json(&FrontMatter::Toml("date = 2024-01-02\n")) {
+            Ok(x) => x,
+            Err(y) => panic!("{}", y.message),
+        };
+
+        assert_eq!(value["date"], Value::String("2024-01-02".to_string()));
+    }
+
+    #[test]
+    fn yaml_errors_have_file_position() {
+        let error = match front_matter_to_json(&FrontMatter::Yaml("title: A\ndate: [\n")) {
+            Ok(_) => panic!("expected an error"),
+            Err(x) => x,
+        };
+
+        // The first line of the front matter is the second line of the file
+        assert_eq!(error.line, Some(4));
+        assert_eq!(error.column, Some(1));
+        assert!(!error.message.contains("at line"));
+    }
+
+    #[test]
+    fn toml_errors_have_file_position() {
+        let error = match front_matter_to_json(&FrontMatter::Toml("title = \"A\"\ndate = \n")) {
+            Ok(_) => panic!("expected an error"),
+            Err(x) => x,
+        };
+
+        assert_eq!(error.line, Some(3));
+        assert_eq!(error.column, Some(8));
+    }
+}
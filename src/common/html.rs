@@ -0,0 +1,37 @@
+/// Escapes text for use in HTML, including inside a quoted attribute
+pub fn escape_html(text: &str) -> String {
+    return text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;");
+}
+
+/// Turns the entities the rendered markdown and `escape_html` use back into
+/// text. Any other entity is left as it is
+pub fn decode_html(text: &str) -> String {
+    return text
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#x27;", "'")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_html, escape_html};
+
+    #[test]
+    fn decodes_what_is_escaped() {
+        let text = "<a href=\"x\">Tom & Jerry's</a> &amp;";
+
+        assert_eq!(
+            escape_html(text),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry's&lt;/a&gt; &amp;amp;"
+        );
+        assert_eq!(decode_html(&escape_html(text)), text);
+        assert_eq!(decode_html("it&#39;s &copy;"), "it's &copy;");
+    }
+}
@@ -1,36 +1,60 @@
-pub mod preview;
+pub mod front_matter;
+mod html;
+mod reading;
 mod types;
 
-use markdown::{mdast::Node, to_mdast, ParseOptions};
-pub use types::{BlogError, BlogJson};
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use front_matter::{front_matter_to_json, split_front_matter};
+pub use html::{decode_html, escape_html};
+use markdown::{mdast::Node, to_html_with_options, to_mdast};
+pub use reading::{count_words, is_cjk, reading_time};
+pub use types::{parse_publish_at, BlogError, BlogJson};
 
 use std::{
+    ffi::OsStr,
     fs,
     path::{Path, PathBuf},
 };
 
 use walkdir::WalkDir;
 
-use crate::Blog;
+#[cfg(feature = "highlight")]
+use crate::render::highlight;
+use crate::{
+    preview::strip_excerpt_marker,
+    render::{sanitize, HtmlSafety, RenderOptions},
+    tags::{normalise_tags, Tag},
+    toc::{add_heading_ids, heading_sources},
+    Blog, BlogOptions,
+};
 
 // TODO: Better name?
 pub fn parse_blogs<T: AsRef<Path>, U: Blog>(
     base: T,
     toc_generation_func: Option<&dyn Fn(&Node) -> String>,
     preview_chars: Option<usize>,
-) -> Result<(Vec<U>, Vec<String>), BlogError> {
-    let blog_paths = get_blog_paths(base)?;
+    blog_options: &BlogOptions,
+) -> Result<(Vec<U>, Vec<Tag>), BlogError> {
+    let blog_paths = blog_options.layout.blog_paths(base.as_ref())?;
 
     let mut entries = vec![];
     let mut tags = vec![]; // TODO: would it be worth converting tags into a Set<String>?
 
     for blog_path in blog_paths {
-        let out: U = process_blogs(blog_path, toc_generation_func, preview_chars)?;
+        let out: U = process_blogs(blog_path, toc_generation_func, preview_chars, blog_options)?;
+
+        if !is_published(out.is_draft(), out.get_publish_at(), blog_options) {
+            continue;
+        }
+
         entries.push(out.clone());
 
-        for tag in &out.get_tags() {
-            if !tags.contains(tag) {
-                tags.push(tag.clone());
+        for (name, slug) in out.get_tags().into_iter().zip(out.get_tag_slugs()) {
+            if !tags.iter().any(|x: &Tag| x.slug == slug) {
+                tags.push(Tag {
+                    name: name,
+                    slug: slug,
+                });
             }
         }
     }
@@ -38,29 +62,30 @@ pub fn parse_blogs<T: AsRef<Path>, U: Blog>(
     return Ok((entries, tags));
 }
 
-pub fn get_blog_paths<T: AsRef<Path>>(base: T) -> Result<Vec<PathBuf>, BlogError> {
+/// Finds every markdown file underneath `base`, newest first when the blog is
+/// organised by date. `max_depth` limits how deep the search goes
+pub fn get_blog_paths<T: AsRef<Path>>(
+    base: T,
+    max_depth: Option<usize>,
+) -> Result<Vec<PathBuf>, BlogError> {
     let base = base.as_ref();
     if !base.is_dir() {
-        panic!("BLOG_ROOT is not a directory!")
+        return Err(BlogError::NotADirectory(base.to_path_buf()));
     }
     let mut markdown_files: Vec<PathBuf> = Vec::new();
 
-    for entry in WalkDir::new(base) {
+    let walker = match max_depth {
+        Some(x) => WalkDir::new(base).max_depth(x),
+        None => WalkDir::new(base),
+    };
+
+    for entry in walker {
         let entry = match entry {
             Ok(x) => x,
             Err(y) => return Err(BlogError::File(y.into())),
         };
 
-        let name = match entry.file_name().to_str() {
-            Some(x) => x,
-            None => continue,
-        };
-
-        if name.contains(".json") {
-            continue;
-        }
-
-        if !(name.contains(".md") || name.contains(".html")) {
+        if !is_blog_file(entry.path()) {
             continue;
         }
 
@@ -71,7 +96,16 @@ pub fn get_blog_paths<T: AsRef<Path>>(base: T) -> Result<Vec<PathBuf>, BlogError
     Ok(markdown_files)
 }
 
-pub fn get_json_data<T: AsRef<Path>>(blog: T) -> Result<BlogJson, BlogError> {
+/// Whether the file is a blog, going by its extension. Only `.md` and `.html`
+/// files are blogs, so files such as `notes.md.bak` or `draft.mdx` are not
+pub fn is_blog_file(path: &Path) -> bool {
+    return path.extension() == Some(OsStr::new("md"))
+        || path.extension() == Some(OsStr::new("html"));
+}
+
+/// Finds the path of the sidecar JSON for a blog, which sits next to the
+/// markdown with the same name
+pub fn get_json_path<T: AsRef<Path>>(blog: T) -> Result<PathBuf, BlogError> {
     let blog = blog.as_ref();
 
     let parent_path = match blog.parent() {
@@ -91,31 +125,206 @@ pub fn get_json_data<T: AsRef<Path>>(blog: T) -> Result<BlogJson, BlogError> {
 
     let name_split: Vec<&str> = file_str.split(".").collect();
 
-    let n = match name_split.get(0) {
+    let n = match name_split.first() {
         Some(&x) => x,
         None => return Err(BlogError::ImproperFileName(file_str.to_string())),
     };
 
     let name = format!("{}.json", n);
 
-    let json_path = parent_path.join(name);
+    return Ok(parent_path.join(name));
+}
+
+pub fn get_json_data<T: AsRef<Path>>(blog: T) -> Result<BlogJson, BlogError> {
+    let json_path = get_json_path(blog)?;
     let json_text = match fs::read_to_string(json_path) {
         Ok(x) => x,
         Err(y) => return Err(BlogError::File(y)),
     };
 
-    let json_data: BlogJson = serde_json::from_str(&json_text).unwrap();
-    return Ok(json_data);
+    return match serde_json::from_str(&json_text) {
+        Ok(x) => Ok(x),
+        Err(y) => Err(BlogError::Json(y)),
+    };
+}
+
+/// Whether a blog should be included, given its `draft` and `publish_at`
+pub fn is_published(
+    draft: bool,
+    publish_at: Option<NaiveDateTime>,
+    blog_options: &BlogOptions,
+) -> bool {
+    if blog_options.include_drafts {
+        return true;
+    }
+
+    if draft {
+        return false;
+    }
+
+    return match publish_at {
+        Some(x) => x <= Utc::now().naive_utc(),
+        None => true,
+    };
+}
+
+/// Reads a blog post, returning its metadata and the markdown body.
+///
+/// Metadata can either be front matter at the top of the markdown (YAML fenced
+/// by `---` or TOML fenced by `+++`) or a sidecar JSON next to the markdown.
+/// If a post has front matter then it takes precedence and any sidecar JSON is
+/// ignored entirely - the two are never merged
+pub fn get_blog_data<T: AsRef<Path>>(
+    blog: T,
+    blog_options: &BlogOptions,
+) -> Result<(BlogJson, String), BlogError> {
+    let text = match fs::read_to_string(&blog) {
+        Ok(x) => x,
+        Err(y) => return Err(BlogError::File(y)),
+    };
+
+    let (front_matter, body) = match split_front_matter(&text) {
+        Some(x) => x,
+        None => {
+            let json_data = normalise_json_tags(get_json_data(&blog)?, blog_options);
+            return Ok((json_data, text));
+        }
+    };
+
+    let value = match front_matter_to_json(&front_matter) {
+        Ok(x) => x,
+        Err(y) => {
+            return Err(BlogError::FrontMatter {
+                message: y.message,
+                line: y.line,
+                column: y.column,
+            })
+        }
+    };
+
+    let json_data: BlogJson = match serde_json::from_value(value) {
+        Ok(x) => x,
+        Err(y) => {
+            return Err(BlogError::FrontMatter {
+                message: y.to_string(),
+                line: None,
+                column: None,
+            })
+        }
+    };
+
+    let json_data = normalise_json_tags(json_data, blog_options);
+
+    return Ok((json_data, body.to_string()));
+}
+
+fn normalise_json_tags(mut json_data: BlogJson, blog_options: &BlogOptions) -> BlogJson {
+    let (names, slugs) = normalise_tags(&json_data.tags, &blog_options.tags);
+
+    json_data.tags = names;
+    json_data.tag_slugs = slugs;
+
+    return json_data;
+}
+
+/// Uses the layout to find the blog with this date and slug, returning its
+/// metadata and markdown body. Returns `None` if there is no such blog
+///
+/// Other blogs which fail to parse are skipped, so one broken blog doesn't
+/// stop the rest from being found. A broken blog named after the slug, either
+/// by its file or its folder, is still an error
+pub fn find_blog<T: AsRef<Path>>(
+    base: T,
+    date: NaiveDate,
+    slug: &str,
+    blog_options: &BlogOptions,
+) -> Result<Option<(BlogJson, String)>, BlogError> {
+    let candidates = blog_options
+        .layout
+        .candidate_paths(base.as_ref(), date, slug)?;
+
+    let mut error = None;
+
+    for path in candidates {
+        let named = path.file_stem() == Some(OsStr::new(slug))
+            || path.parent().and_then(|x| x.file_name()) == Some(OsStr::new(slug));
+
+        let (json, markdown) = match get_blog_data(path, blog_options) {
+            Ok(x) => x,
+            Err(y) => {
+                if named && error.is_none() {
+                    error = Some(y);
+                }
+                continue;
+            }
+        };
+
+        if json.slug == slug && json.date == date {
+            return Ok(Some((json, markdown)));
+        }
+    }
+
+    return match error {
+        Some(x) => Err(x),
+        None => Ok(None),
+    };
+}
+
+/// Renders the markdown body of a blog post into HTML. Every heading is given
+/// an `id` to match the built in table of contents.
+///
+/// `safety` is the blog's own `safety` from its metadata, if it has one
+pub fn render_markdown(
+    markdown: &str,
+    safety: Option<HtmlSafety>,
+    blog_options: &BlogOptions,
+) -> Result<String, BlogError> {
+    let render_options = &blog_options.render;
+
+    let markdown = strip_excerpt_marker(markdown, blog_options);
+
+    let options = render_options.markdown_options(safety);
+
+    let html = match to_html_with_options(&markdown, &options) {
+        Ok(x) => x,
+        Err(y) => return Err(BlogError::Markdown(y.to_string())),
+    };
+
+    // Headings in raw HTML don't get an id, to match the table of contents.
+    // The markdown is only parsed again when there could be one
+    let from_markdown = if options.compile.allow_dangerous_html && markdown.contains("<h") {
+        match to_mdast(&markdown, &options.parse) {
+            Ok(x) => heading_sources(&x),
+            Err(y) => return Err(BlogError::Markdown(y.to_string())),
+        }
+    } else {
+        vec![]
+    };
+
+    let html = match render_options.get_safety(safety) {
+        HtmlSafety::Sanitized => sanitize(&html, &render_options.sanitize),
+        _ => html,
+    };
+
+    // After sanitizing, so the highlighting isn't removed
+    #[cfg(feature = "highlight")]
+    let html = match &render_options.highlight {
+        Some(x) => highlight(&html, x)?,
+        None => html,
+    };
+
+    return Ok(add_heading_ids(&html, &from_markdown));
 }
 
 pub fn toc(
-    markdown: &String,
+    markdown: &str,
     toc_generation_func: Option<&dyn Fn(&Node) -> String>,
+    render_options: &RenderOptions,
 ) -> Result<Option<String>, BlogError> {
     return if let Some(toc_gen) = toc_generation_func {
-        let mdast = match to_mdast(&markdown, &ParseOptions::default()) {
+        let mdast = match to_mdast(markdown, &render_options.markdown_options(None).parse) {
             Ok(x) => x,
-            Err(y) => return Err(BlogError::Markdown(y)),
+            Err(y) => return Err(BlogError::Markdown(y.to_string())),
         };
 
         Ok(Some(toc_gen(&mdast)))
@@ -128,6 +337,116 @@ fn process_blogs<T: AsRef<Path>, U: Blog>(
     blog: T,
     toc_generation_func: Option<&dyn Fn(&Node) -> String>,
     preview_chars: Option<usize>,
+    blog_options: &BlogOptions,
 ) -> Result<U, BlogError> {
-    return U::create(blog, toc_generation_func, preview_chars);
+    return U::create(blog, toc_generation_func, preview_chars, blog_options);
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate, Utc};
+
+    use super::{find_blog, get_blog_data, is_published, parse_publish_at};
+    use crate::{layout::FlatLayout, testing::TempBlog, BlogError, BlogOptions};
+
+    #[test]
+    fn skips_other_broken_blogs() {
+        let base = TempBlog::new();
+
+        base.write(
+            "good.md",
+            "---\ntitle: Good\ndate: 2024-01-02\ndesc: A blog\nslug: good\ntags: []\n---\nHello",
+        );
+        base.write("broken.md", "---\ntitle: [\n---\nHello");
+
+        let blog_options = BlogOptions {
+            layout: Box::new(FlatLayout),
+            ..BlogOptions::default()
+        };
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+
+        let found = find_blog(&base, date, "good", &blog_options).unwrap();
+        assert_eq!(found.unwrap().0.title, "Good");
+
+        assert!(find_blog(&base, date, "missing", &blog_options)
+            .unwrap()
+            .is_none());
+        assert!(find_blog(&base, date, "broken", &blog_options).is_err());
+    }
+
+    #[test]
+    fn front_matter_beats_sidecar() {
+        let base = TempBlog::new();
+
+        let sidecar = r#"{"title": "Sidecar", "date": "2024-01-02", "desc": "A blog", "slug": "a", "tags": []}"#;
+
+        base.write("a.json", sidecar);
+        let front_matter = base.write(
+            "a.md",
+            "---\ntitle: Front Matter\ndate: 2024-01-02\ndesc: A blog\nslug: a\ntags: []\n---\nHello",
+        );
+        base.write("b.json", sidecar);
+        let plain = base.write("b.md", "Hello");
+
+        let blog_options = BlogOptions::default();
+
+        let (json, markdown) = get_blog_data(front_matter, &blog_options).unwrap();
+        assert_eq!(json.title, "Front Matter");
+        assert_eq!(markdown, "Hello");
+
+        let (json, markdown) = get_blog_data(plain, &blog_options).unwrap();
+        assert_eq!(json.title, "Sidecar");
+        assert_eq!(markdown, "Hello");
+    }
+
+    #[test]
+    fn drafts_and_scheduled_blogs_are_hidden() {
+        let past = Some(Utc::now().naive_utc() - Duration::days(1));
+        let future = Some(Utc::now().naive_utc() + Duration::days(1));
+
+        let mut blog_options = BlogOptions::default();
+
+        assert!(is_published(false, None, &blog_options));
+        assert!(is_published(false, past, &blog_options));
+        assert!(!is_published(false, future, &blog_options));
+        assert!(!is_published(true, None, &blog_options));
+        assert!(!is_published(true, past, &blog_options));
+
+        blog_options.include_drafts = true;
+
+        assert!(is_published(true, None, &blog_options));
+        assert!(is_published(false, future, &blog_options));
+    }
+
+    #[test]
+    fn parses_publish_at() {
+        let expected = NaiveDate::from_ymd_opt(2024, 1, 2)
+            .unwrap()
+            .and_hms_opt(9, 30, 0);
+
+        assert_eq!(parse_publish_at("2024-01-02T09:30:00"), expected);
+        assert_eq!(parse_publish_at("2024-01-02 09:30:00"), expected);
+        assert_eq!(parse_publish_at("2024-01-02T10:30:00+01:00"), expected);
+        assert_eq!(
+            parse_publish_at("2024-01-02"),
+            NaiveDate::from_ymd_opt(2024, 1, 2)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+        );
+        assert_eq!(parse_publish_at("tomorrow"), None);
+    }
+
+    #[test]
+    fn front_matter_errors_have_file_position() {
+        let base = TempBlog::new();
+        let path = base.write("a.md", "---\ntitle: A\ndate: [\n---\nHello");
+
+        match get_blog_data(path, &BlogOptions::default()) {
+            Err(BlogError::FrontMatter { line, column, .. }) => {
+                assert_eq!(line, Some(4));
+                assert_eq!(column, Some(1));
+            }
+            _ => panic!("expected a front matter error"),
+        }
+    }
 }
@@ -0,0 +1,56 @@
+use markdown::{mdast::Node, to_mdast};
+
+use crate::{render::RenderOptions, types::BlogOptions};
+
+use super::BlogError;
+
+/// Counts the words in the markdown. Code blocks and raw HTML are not
+/// counted, and as Chinese and Japanese are written without spaces each of
+/// their characters is counted as a word
+pub fn count_words(markdown: &str, render_options: &RenderOptions) -> Result<usize, BlogError> {
+    let mdast = match to_mdast(markdown, &render_options.markdown_options(None).parse) {
+        Ok(x) => x,
+        Err(y) => return Err(BlogError::Markdown(y.to_string())),
+    };
+
+    return Ok(node_words(&mdast));
+}
+
+/// The estimated reading time in minutes, rounded up
+pub fn reading_time(word_count: usize, blog_options: &BlogOptions) -> usize {
+    let words_per_minute = blog_options.words_per_minute.max(1);
+
+    return word_count.div_ceil(words_per_minute);
+}
+
+fn node_words(node: &Node) -> usize {
+    return match node {
+        Node::Text(x) => text_words(&x.value),
+        Node::InlineCode(x) => text_words(&x.value),
+        Node::Code(_) | Node::Math(_) | Node::Html(_) => 0,
+        _ => match node.children() {
+            Some(x) => x.iter().map(node_words).sum(),
+            None => 0,
+        },
+    };
+}
+
+fn text_words(text: &str) -> usize {
+    let mut words = 0;
+
+    for word in text.split_whitespace() {
+        let characters = word.chars().filter(|x| is_cjk(*x)).count();
+
+        words += match characters {
+            0 => 1,
+            x => x,
+        };
+    }
+
+    return words;
+}
+
+// Kana and the CJK ideographs
+pub fn is_cjk(c: char) -> bool {
+    return matches!(c, '\u{3040}'..='\u{30ff}' | '\u{3400}'..='\u{4dbf}' | '\u{4e00}'..='\u{9fff}');
+}
@@ -1,7 +1,10 @@
-use std::{fmt::Display, io};
+use std::{fmt::Display, io, path::PathBuf};
 
-use chrono::NaiveDate;
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+
+use crate::render::HtmlSafety;
 
 use std::error::Error;
 
@@ -12,22 +15,92 @@ pub struct BlogJson {
     pub desc: Option<String>,
     pub slug: String,
     pub tags: Vec<String>,
+    #[serde(skip)]
+    pub tag_slugs: Vec<String>, // Filled in once the tags are normalised
     pub keywords: Option<Vec<String>>,
     pub canonical_link: Option<String>,
     pub author_name: Option<String>,
     pub author_webpage: Option<String>,
     pub last_modified: Option<NaiveDate>, // For sitemap, if not present uses `date`
     pub priority: Option<f64>,            // For sitemap, if not present uses the default
+    #[serde(default)]
+    pub draft: bool, // Never published unless `include_drafts` is set
+    #[serde(default, deserialize_with = "deserialize_publish_at")]
+    pub publish_at: Option<NaiveDateTime>, // Not published until this time, in UTC
+    pub safety: Option<HtmlSafety>, // Can only make the `safety` in the `RenderOptions` stricter
+    #[serde(flatten)]
+    pub extra: Map<String, Value>, // Every other field, kept for the user
+}
+
+/// `publish_at` can be an RFC 3339 timestamp (which is converted to UTC), a
+/// timestamp without an offset (which is taken as UTC) or just a date
+fn deserialize_publish_at<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<NaiveDateTime>, D::Error> {
+    let text = match Option::<String>::deserialize(deserializer)? {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+
+    return match parse_publish_at(&text) {
+        Some(x) => Ok(Some(x)),
+        None => Err(serde::de::Error::custom(format!(
+            "`{}` is not a valid `publish_at` - use yyyy-mm-ddThh:mm:ss",
+            text
+        ))),
+    };
+}
+
+pub fn parse_publish_at(text: &str) -> Option<NaiveDateTime> {
+    if let Ok(x) = DateTime::parse_from_rfc3339(text) {
+        return Some(x.naive_utc());
+    }
+
+    if let Ok(x) = text.parse::<NaiveDateTime>() {
+        return Some(x);
+    }
+
+    if let Ok(x) = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S") {
+        return Some(x);
+    }
+
+    if let Ok(x) = text.parse::<NaiveDate>() {
+        return x.and_hms_opt(0, 0, 0);
+    }
+
+    return None;
 }
 
+/// Every error `blog-tools` can return
 #[derive(Debug)]
 pub enum BlogError {
+    /// A file could not be read
     File(io::Error),
+    /// The markdown could not be rendered, with the reason
     Markdown(String),
+    /// A file which should exist could not be found
     FileNotFound, // TODO: It would be nice to include the file not found here but that would rely on converting a Path to a String - which involves TWO unwraps!
     /// Include the date as found as a string
     ImproperDate(String),
+    /// Include the file name as found as a string
     ImproperFileName(String),
+    /// Front matter which could not be parsed, with the reason
+    FrontMatter {
+        /// The reason the front matter could not be parsed
+        message: String,
+        /// The line of the markdown file the problem is on, where it is known
+        line: Option<usize>,
+        /// The column of the line the problem is at, where it is known
+        column: Option<usize>,
+    },
+    /// JSON which could not be parsed, such as a sidecar JSON
+    Json(serde_json::Error),
+    /// The blog root is not a directory
+    NotADirectory(PathBuf),
+    /// Syntax highlighting failed, with the reason
+    Highlight(String),
+    /// A tags file which could not be parsed, with the reason
+    TagsFile(String),
 }
 
 impl Error for BlogError {}
@@ -40,6 +113,22 @@ impl Display for BlogError {
             BlogError::FileNotFound => write!(f, "File not found"),
             BlogError::ImproperDate(c) => write!(f, "Found date `{}` which appears to be improper - dates should be in the yyyy-mm-dd format", c),
             BlogError::ImproperFileName(d) => write!(f, "Found file name `{}` which appears to be improper", d),
+            BlogError::FrontMatter {
+                message,
+                line: Some(l),
+                column: Some(c),
+            } => write!(
+                f,
+                "Front matter parsing error at line {}, column {} caused by: {}",
+                l, c, message
+            ),
+            BlogError::FrontMatter { message, .. } => {
+                write!(f, "Front matter parsing error caused by: {}", message)
+            }
+            BlogError::Json(g) => write!(f, "JSON parsing error caused by: {}", g),
+            BlogError::NotADirectory(h) => write!(f, "`{}` is not a directory", h.display()),
+            BlogError::Highlight(i) => write!(f, "Syntax highlighting error caused by: {}", i),
+            BlogError::TagsFile(j) => write!(f, "Tags file parsing error caused by: {}", j),
         }
     }
 }
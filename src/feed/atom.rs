@@ -0,0 +1,289 @@
+use std::{io::Cursor, path::Path};
+
+use chrono::{DateTime, SecondsFormat};
+use xml::{writer::XmlEvent, EmitterConfig, EventWriter};
+
+use crate::{
+    common::{parse_blogs, BlogError},
+    low::LowBlogEntry,
+    sitemap::SitemapOptions,
+    tags::Tag,
+    types::{Blog, BlogOptions},
+};
+
+use super::{
+    items::{
+        blog_link, date_time, feed_entries, find_tag, item_content, write_text_element, Channel,
+    },
+    types::FeedOptions,
+};
+
+/// Use this function in `low` mode to generate an Atom 1.0 feed
+///
+/// Parameters
+///
+/// - `blog_root`: Path to the root of the blog e.g. `files/blog`
+/// - `url_base`: URL of the website e.g. `www.example.com`
+/// - `sitemap_options`: `SitemapOptions`, used to build the links to each blog
+/// - `options`: `FeedOptions` for configuration
+/// - `blog_options`: `BlogOptions`, used to decide which blogs are included
+pub fn create_atom_feed<T: AsRef<Path>>(
+    blog_root: T,
+    url_base: &String,
+    sitemap_options: &SitemapOptions,
+    options: &FeedOptions,
+    blog_options: &BlogOptions,
+) -> Result<String, BlogError> {
+    let (entries, _): (Vec<LowBlogEntry>, Vec<Tag>) =
+        parse_blogs(blog_root, None, None, blog_options)?;
+
+    let channel = Channel::blog(url_base, sitemap_options, options);
+
+    return create_atom_feed_inner(&entries, &channel, url_base, sitemap_options, options);
+}
+
+/// Use this function in `low` mode to generate an Atom 1.0 feed containing
+/// only the blogs with the specified tag. The feed belongs at
+/// `{url_base}/{tag_root_slug}/{tag_slug}/{atom_file_name}`
+///
+/// Parameters
+///
+/// - `blog_root`: Path to the root of the blog e.g. `files/blog`
+/// - `tag`: The tag to generate the feed for, either its name or its slug
+/// - `url_base`: URL of the website e.g. `www.example.com`
+/// - `sitemap_options`: `SitemapOptions`, used to build the links to each blog
+/// - `options`: `FeedOptions` for configuration
+/// - `blog_options`: `BlogOptions`, used to decide which blogs are included
+pub fn create_tag_atom_feed<T: AsRef<Path>>(
+    blog_root: T,
+    tag: &str,
+    url_base: &String,
+    sitemap_options: &SitemapOptions,
+    options: &FeedOptions,
+    blog_options: &BlogOptions,
+) -> Result<String, BlogError> {
+    let (entries, tags): (Vec<LowBlogEntry>, Vec<Tag>) =
+        parse_blogs(blog_root, None, None, blog_options)?;
+
+    let tag = find_tag(tag, &tags, blog_options);
+
+    let channel = Channel::tag(&tag, url_base, sitemap_options, options);
+
+    return create_atom_feed_inner(&entries, &channel, url_base, sitemap_options, options);
+}
+
+pub fn create_atom_feed_inner<T: Blog>(
+    entries: &[T],
+    channel: &Channel,
+    url_base: &String,
+    sitemap_options: &SitemapOptions,
+    options: &FeedOptions,
+) -> Result<String, BlogError> {
+    let mut buffer = Cursor::new(Vec::new());
+    let mut writer = EmitterConfig::new()
+        .perform_indent(true)
+        .create_writer(&mut buffer);
+
+    let entries = feed_entries(entries, channel, options);
+
+    let self_link = channel.feed_link(&options.atom_file_name);
+
+    writer
+        .write(XmlEvent::start_element("feed").attr("xmlns", "http://www.w3.org/2005/Atom"))
+        .unwrap();
+
+    write_text_element(&mut writer, "title", &channel.title);
+
+    if !options.description.is_empty() {
+        write_text_element(&mut writer, "subtitle", &options.description);
+    }
+
+    write_link(&mut writer, &channel.home_link, None);
+    write_link(&mut writer, &self_link, Some("self"));
+    write_text_element(&mut writer, "id", &self_link);
+
+    // An Atom feed must always have an `updated`. An empty blog uses the
+    // Unix epoch, so the feed is the same every time it is generated
+    let updated = match entries.iter().map(|x| updated_date(*x)).max() {
+        Some(x) => date_time(x),
+        None => DateTime::UNIX_EPOCH,
+    };
+
+    write_text_element(
+        &mut writer,
+        "updated",
+        &updated.to_rfc3339_opts(SecondsFormat::Secs, true),
+    );
+
+    // A feed needs an author unless every entry has its own, so the title of
+    // the feed stands in when there is no `author_name`
+    match &options.author_name {
+        Some(x) => write_author(&mut writer, x, None),
+        None => {
+            if entries.iter().any(|x| x.get_author_name().is_none()) {
+                write_author(&mut writer, &channel.title, None);
+            }
+        }
+    }
+
+    for blog in entries {
+        writer.write(XmlEvent::start_element("entry")).unwrap();
+
+        let link = blog_link(blog, url_base, sitemap_options);
+
+        write_text_element(&mut writer, "title", &blog.get_title());
+        write_link(&mut writer, &link, None);
+        write_text_element(
+            &mut writer,
+            "id",
+            &entry_id(blog, url_base, sitemap_options),
+        );
+        write_text_element(
+            &mut writer,
+            "published",
+            &date_time(blog.get_date_listed()).to_rfc3339_opts(SecondsFormat::Secs, true),
+        );
+        write_text_element(
+            &mut writer,
+            "updated",
+            &date_time(updated_date(blog)).to_rfc3339_opts(SecondsFormat::Secs, true),
+        );
+
+        if let Some(author_name) = blog.get_author_name() {
+            write_author(&mut writer, &author_name, blog.get_author_webpage());
+        }
+
+        for tag in blog.get_tags() {
+            writer
+                .write(XmlEvent::start_element("category").attr("term", &tag))
+                .unwrap();
+            writer.write(XmlEvent::end_element()).unwrap();
+        }
+
+        let content_element = if options.full_html {
+            "content"
+        } else {
+            "summary"
+        };
+
+        writer
+            .write(XmlEvent::start_element(content_element).attr("type", "html"))
+            .unwrap();
+        writer
+            .write(XmlEvent::characters(&item_content(blog, options)))
+            .unwrap();
+        writer.write(XmlEvent::end_element()).unwrap();
+
+        writer.write(XmlEvent::end_element()).unwrap(); // Finish <entry>
+    }
+
+    writer.write(XmlEvent::end_element()).unwrap(); // End <feed>
+
+    return Ok(String::from_utf8(buffer.into_inner()).unwrap());
+}
+
+fn updated_date<T: Blog>(blog: &T) -> chrono::NaiveDate {
+    return match blog.get_last_modified() {
+        Some(x) => x,
+        None => blog.get_date_listed(),
+    };
+}
+
+/// Atom ids must never change, so rather than the link (which changes with
+/// the domain) a tag URI is built from the full slug e.g.
+/// `tag:www.example.com,2024-03-19:blog/2024-03-19/my-blog`
+fn entry_id<T: Blog>(blog: &T, url_base: &str, sitemap_options: &SitemapOptions) -> String {
+    let without_scheme = match url_base.split_once("://") {
+        Some((_, x)) => x,
+        None => url_base,
+    };
+
+    let authority = match without_scheme.split(['/', ':']).next() {
+        Some(x) => x,
+        None => without_scheme,
+    };
+
+    return format!(
+        "tag:{},{}:{}/{}",
+        authority,
+        blog.get_date_listed(),
+        sitemap_options.blog_root_slug,
+        blog.get_full_slug()
+    );
+}
+
+fn write_link<W: std::io::Write>(writer: &mut EventWriter<W>, href: &str, rel: Option<&str>) {
+    let element = XmlEvent::start_element("link").attr("href", href);
+
+    let element = match rel {
+        Some(x) => element.attr("rel", x),
+        None => element,
+    };
+
+    writer.write(element).unwrap();
+    writer.write(XmlEvent::end_element()).unwrap();
+}
+
+fn write_author<W: std::io::Write>(
+    writer: &mut EventWriter<W>,
+    name: &str,
+    webpage: Option<String>,
+) {
+    writer.write(XmlEvent::start_element("author")).unwrap();
+    write_text_element(writer, "name", name);
+
+    if let Some(uri) = webpage {
+        write_text_element(writer, "uri", &uri);
+    }
+
+    writer.write(XmlEvent::end_element()).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{create_atom_feed_inner, Channel, FeedOptions};
+    use crate::{low::LowBlogEntry, sitemap::SitemapOptions, testing::low_entry};
+
+    fn atom(entries: &[LowBlogEntry], options: &FeedOptions) -> String {
+        let url_base = "www.example.com".to_string();
+        let sitemap_options = SitemapOptions::default();
+        let channel = Channel::blog(&url_base, &sitemap_options, options);
+
+        return create_atom_feed_inner(entries, &channel, &url_base, &sitemap_options, options)
+            .unwrap();
+    }
+
+    #[test]
+    fn feed_always_has_an_author() {
+        let options = FeedOptions {
+            title: "My Blog".to_string(),
+            ..FeedOptions::default()
+        };
+
+        let feed = atom(&[low_entry(json!({}))], &options);
+        assert!(feed.contains("<author>\n    <name>My Blog</name>\n  </author>"));
+
+        let feed = atom(&[low_entry(json!({ "author_name": "Ann" }))], &options);
+        assert!(!feed.contains("<name>My Blog</name>"));
+        assert!(feed.contains("<name>Ann</name>"));
+
+        let options = FeedOptions {
+            author_name: Some("Bob".to_string()),
+            ..options
+        };
+
+        let feed = atom(&[low_entry(json!({}))], &options);
+        assert!(feed.contains("<name>Bob</name>"));
+        assert!(!feed.contains("<name>My Blog</name>"));
+    }
+
+    #[test]
+    fn empty_feed_is_reproducible() {
+        let feed = atom(&[], &FeedOptions::default());
+
+        assert!(feed.contains("<updated>1970-01-01T00:00:00Z</updated>"));
+        assert_eq!(feed, atom(&[], &FeedOptions::default()));
+    }
+}
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+use crate::{common::BlogError, sitemap::SitemapOptions, tags::Tag, types::Blog};
+
+use super::{
+    atom::create_atom_feed_inner, items::Channel, json::create_json_feed_inner,
+    rss::create_rss_feed_inner, types::FeedOptions,
+};
+
+/// Every feed format generated for a blog. The tag feeds are keyed by the slug
+/// of the tag, and are empty unless `include_tag_feeds` is set
+pub struct Feeds {
+    pub rss: String,
+    pub atom: String,
+    pub json: String,
+    pub tag_rss: HashMap<String, String>,
+    pub tag_atom: HashMap<String, String>,
+    pub tag_json: HashMap<String, String>,
+}
+
+pub fn create_feeds_inner<T: Blog>(
+    entries: &[T],
+    tags: &Vec<Tag>,
+    url_base: &String,
+    sitemap_options: &SitemapOptions,
+    options: &FeedOptions,
+) -> Result<Feeds, BlogError> {
+    let channel = Channel::blog(url_base, sitemap_options, options);
+
+    let mut feeds = Feeds {
+        rss: create_rss_feed_inner(entries, &channel, url_base, sitemap_options, options)?,
+        atom: create_atom_feed_inner(entries, &channel, url_base, sitemap_options, options)?,
+        json: create_json_feed_inner(entries, &channel, url_base, sitemap_options, options)?,
+        tag_rss: HashMap::new(),
+        tag_atom: HashMap::new(),
+        tag_json: HashMap::new(),
+    };
+
+    if !options.include_tag_feeds {
+        return Ok(feeds);
+    }
+
+    for tag in tags {
+        let channel = Channel::tag(tag, url_base, sitemap_options, options);
+
+        feeds.tag_rss.insert(
+            tag.slug.clone(),
+            create_rss_feed_inner(entries, &channel, url_base, sitemap_options, options)?,
+        );
+        feeds.tag_atom.insert(
+            tag.slug.clone(),
+            create_atom_feed_inner(entries, &channel, url_base, sitemap_options, options)?,
+        );
+        feeds.tag_json.insert(
+            tag.slug.clone(),
+            create_json_feed_inner(entries, &channel, url_base, sitemap_options, options)?,
+        );
+    }
+
+    return Ok(feeds);
+}
@@ -0,0 +1,132 @@
+use std::{cmp::Reverse, io::Write};
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use xml::{writer::XmlEvent, EventWriter};
+
+use crate::{
+    sitemap::SitemapOptions,
+    tags::{encode_slug, normalise_tag, Tag},
+    types::{Blog, BlogOptions},
+};
+
+use super::types::FeedOptions;
+
+/// Describes which feed is being generated - either the feed for the whole
+/// blog, or the feed for a single tag
+pub struct Channel {
+    /// Title of the feed
+    pub title: String,
+    /// URL of the page the feed belongs to, e.g. the blog index or a tag page.
+    /// The feed files are found underneath this URL
+    pub home_link: String,
+    /// The slug of the tag of the feed, or `None` for the whole blog
+    pub tag: Option<String>,
+}
+
+impl Channel {
+    /// The feed for the whole blog, found underneath `blog_root_slug`
+    pub fn blog(
+        url_base: &String,
+        sitemap_options: &SitemapOptions,
+        options: &FeedOptions,
+    ) -> Self {
+        return Self {
+            title: options.title.clone(),
+            home_link: format!("{}/{}", url_base, sitemap_options.blog_root_slug),
+            tag: None,
+        };
+    }
+
+    /// The feed for a single tag, found underneath `tag_root_slug` e.g.
+    /// `www.example.com/blog/tag/science/feed.xml`
+    pub fn tag(
+        tag: &Tag,
+        url_base: &String,
+        sitemap_options: &SitemapOptions,
+        options: &FeedOptions,
+    ) -> Self {
+        return Self {
+            title: format!("{} - {}", options.title, tag.name),
+            home_link: format!(
+                "{}/{}/{}",
+                url_base,
+                sitemap_options.tag_root_slug,
+                encode_slug(&tag.slug)
+            ),
+            tag: Some(tag.slug.clone()),
+        };
+    }
+
+    /// The URL of one of the feed files of this channel
+    pub fn feed_link(&self, file_name: &String) -> String {
+        return format!("{}/{}", self.home_link, file_name);
+    }
+}
+
+/// Finds the tag with the given slug, name or alias. A tag which no blog has
+/// yet still gets a feed, which is empty
+pub fn find_tag(tag: &str, tags: &[Tag], blog_options: &BlogOptions) -> Tag {
+    let slug = normalise_tag(tag, &blog_options.tags).slug;
+
+    return match tags.iter().find(|x| x.slug == slug) {
+        Some(x) => x.clone(),
+        None => Tag {
+            name: tag.to_string(),
+            slug: slug,
+        },
+    };
+}
+
+/// Selects the blogs which belong in a feed - newest first, up to the limit
+pub fn feed_entries<'a, T: Blog>(
+    entries: &'a [T],
+    channel: &Channel,
+    options: &FeedOptions,
+) -> Vec<&'a T> {
+    let mut selected: Vec<&T> = match &channel.tag {
+        Some(tag) => entries
+            .iter()
+            .filter(|x| x.get_tag_slugs().contains(tag))
+            .collect(),
+        None => entries.iter().collect(),
+    };
+
+    selected.sort_by_key(|x| Reverse(x.get_date_listed()));
+
+    if let Some(limit) = options.limit {
+        selected.truncate(limit);
+    }
+
+    return selected;
+}
+
+/// The content of a single feed item, either the full HTML or the preview
+pub fn item_content<T: Blog>(blog: &T, options: &FeedOptions) -> String {
+    return if options.full_html {
+        blog.get_html()
+    } else {
+        blog.get_preview()
+    };
+}
+
+/// The URL of an individual blog, identical to the one in the sitemap
+pub fn blog_link<T: Blog>(blog: &T, url_base: &String, sitemap_options: &SitemapOptions) -> String {
+    return format!(
+        "{}/{}/{}",
+        url_base,
+        sitemap_options.blog_root_slug,
+        blog.get_full_slug()
+    );
+}
+
+/// Blogs only carry a date, so feeds treat them as published at midnight UTC
+pub fn date_time(date: NaiveDate) -> DateTime<Utc> {
+    return Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap());
+}
+
+/// Writes an element which only contains text e.g. `<title>My Blog</title>`
+pub fn write_text_element<W: Write>(writer: &mut EventWriter<W>, name: &str, text: &str) {
+    writer.write(XmlEvent::start_element(name)).unwrap();
+    writer.write(XmlEvent::characters(text)).unwrap();
+    writer.write(XmlEvent::end_element()).unwrap();
+}
@@ -0,0 +1,171 @@
+use std::path::Path;
+
+use chrono::SecondsFormat;
+
+use crate::{
+    common::{decode_html, parse_blogs, BlogError},
+    low::LowBlogEntry,
+    sitemap::SitemapOptions,
+    tags::Tag,
+    types::{Blog, BlogOptions},
+};
+
+use super::{
+    items::{blog_link, date_time, feed_entries, find_tag, Channel},
+    types::{FeedOptions, JsonFeed, JsonFeedAuthor, JsonFeedItem},
+};
+
+const JSON_FEED_VERSION: &str = "https://jsonfeed.org/version/1.1";
+
+/// Use this function in `low` mode to generate a JSON Feed
+///
+/// Parameters
+///
+/// - `blog_root`: Path to the root of the blog e.g. `files/blog`
+/// - `url_base`: URL of the website e.g. `www.example.com`
+/// - `sitemap_options`: `SitemapOptions`, used to build the links to each blog
+/// - `options`: `FeedOptions` for configuration
+/// - `blog_options`: `BlogOptions`, used to decide which blogs are included
+pub fn create_json_feed<T: AsRef<Path>>(
+    blog_root: T,
+    url_base: &String,
+    sitemap_options: &SitemapOptions,
+    options: &FeedOptions,
+    blog_options: &BlogOptions,
+) -> Result<String, BlogError> {
+    let (entries, _): (Vec<LowBlogEntry>, Vec<Tag>) =
+        parse_blogs(blog_root, None, None, blog_options)?;
+
+    let channel = Channel::blog(url_base, sitemap_options, options);
+
+    return create_json_feed_inner(&entries, &channel, url_base, sitemap_options, options);
+}
+
+/// Use this function in `low` mode to generate a JSON Feed containing only the
+/// blogs with the specified tag. The feed belongs at
+/// `{url_base}/{tag_root_slug}/{tag_slug}/{json_file_name}`
+///
+/// Parameters
+///
+/// - `blog_root`: Path to the root of the blog e.g. `files/blog`
+/// - `tag`: The tag to generate the feed for, either its name or its slug
+/// - `url_base`: URL of the website e.g. `www.example.com`
+/// - `sitemap_options`: `SitemapOptions`, used to build the links to each blog
+/// - `options`: `FeedOptions` for configuration
+/// - `blog_options`: `BlogOptions`, used to decide which blogs are included
+pub fn create_tag_json_feed<T: AsRef<Path>>(
+    blog_root: T,
+    tag: &str,
+    url_base: &String,
+    sitemap_options: &SitemapOptions,
+    options: &FeedOptions,
+    blog_options: &BlogOptions,
+) -> Result<String, BlogError> {
+    let (entries, tags): (Vec<LowBlogEntry>, Vec<Tag>) =
+        parse_blogs(blog_root, None, None, blog_options)?;
+
+    let tag = find_tag(tag, &tags, blog_options);
+
+    let channel = Channel::tag(&tag, url_base, sitemap_options, options);
+
+    return create_json_feed_inner(&entries, &channel, url_base, sitemap_options, options);
+}
+
+pub fn create_json_feed_inner<T: Blog>(
+    entries: &[T],
+    channel: &Channel,
+    url_base: &String,
+    sitemap_options: &SitemapOptions,
+    options: &FeedOptions,
+) -> Result<String, BlogError> {
+    let mut items = vec![];
+
+    for blog in feed_entries(entries, channel, options) {
+        let link = blog_link(blog, url_base, sitemap_options);
+
+        let (content_html, content_text) = if options.full_html {
+            (Some(blog.get_html()), None)
+        } else {
+            // The preview is HTML escaped, but `content_text` is plain text
+            (None, Some(decode_html(&blog.get_preview())))
+        };
+
+        let authors = blog.get_author_name().map(|x| {
+            vec![JsonFeedAuthor {
+                name: Some(x),
+                url: blog.get_author_webpage(),
+            }]
+        });
+
+        let tags = blog.get_tags();
+
+        items.push(JsonFeedItem {
+            id: link.clone(),
+            url: Some(link),
+            title: Some(blog.get_title()),
+            content_html: content_html,
+            content_text: content_text,
+            summary: blog.get_description(),
+            date_published: Some(
+                date_time(blog.get_date_listed()).to_rfc3339_opts(SecondsFormat::Secs, true),
+            ),
+            date_modified: blog
+                .get_last_modified()
+                .map(|x| date_time(x).to_rfc3339_opts(SecondsFormat::Secs, true)),
+            authors: authors,
+            tags: if tags.is_empty() { None } else { Some(tags) },
+        });
+    }
+
+    let feed = JsonFeed {
+        version: JSON_FEED_VERSION.to_string(),
+        title: channel.title.clone(),
+        home_page_url: Some(channel.home_link.clone()),
+        feed_url: Some(channel.feed_link(&options.json_file_name)),
+        description: if options.description.is_empty() {
+            None
+        } else {
+            Some(options.description.clone())
+        },
+        language: options.language.clone(),
+        authors: options.author_name.as_ref().map(|x| {
+            vec![JsonFeedAuthor {
+                name: Some(x.clone()),
+                url: None,
+            }]
+        }),
+        items: items,
+    };
+
+    return Ok(serde_json::to_string_pretty(&feed).unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::{create_json_feed_inner, Channel, FeedOptions};
+    use crate::{sitemap::SitemapOptions, testing::low_entry_with};
+
+    #[test]
+    fn content_text_is_not_escaped() {
+        let entries = vec![low_entry_with(
+            json!({ "title": "Cartoons" }),
+            "<p>Tom &amp; Jerry &lt;3</p>",
+            "Tom &amp; Jerry &lt;3",
+        )];
+
+        let url_base = "www.example.com".to_string();
+        let sitemap_options = SitemapOptions::default();
+        let options = FeedOptions::default();
+        let channel = Channel::blog(&url_base, &sitemap_options, &options);
+
+        let feed =
+            create_json_feed_inner(&entries, &channel, &url_base, &sitemap_options, &options)
+                .unwrap();
+        let feed: Value = serde_json::from_str(&feed).unwrap();
+
+        assert_eq!(feed["items"][0]["content_text"], "Tom & Jerry <3");
+        assert!(feed["items"][0].get("content_html").is_none());
+    }
+}
@@ -0,0 +1,12 @@
+mod atom;
+mod feeds;
+mod items;
+mod json;
+mod rss;
+mod types;
+pub use atom::{create_atom_feed, create_tag_atom_feed};
+pub use json::{create_json_feed, create_tag_json_feed};
+pub use rss::{create_rss_feed, create_tag_rss_feed};
+pub use types::{FeedOptions, JsonFeed, JsonFeedAuthor, JsonFeedItem};
+
+pub(crate) use feeds::create_feeds_inner;
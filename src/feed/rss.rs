@@ -0,0 +1,161 @@
+use std::{io::Cursor, path::Path};
+
+use xml::{writer::XmlEvent, EmitterConfig};
+
+use crate::{
+    common::{parse_blogs, BlogError},
+    low::LowBlogEntry,
+    sitemap::SitemapOptions,
+    tags::Tag,
+    types::{Blog, BlogOptions},
+};
+
+use super::{
+    items::{
+        blog_link, date_time, feed_entries, find_tag, item_content, write_text_element, Channel,
+    },
+    types::FeedOptions,
+};
+
+/// Use this function in `low` mode to generate an RSS 2.0 feed
+///
+/// Parameters
+///
+/// - `blog_root`: Path to the root of the blog e.g. `files/blog`
+/// - `url_base`: URL of the website e.g. `www.example.com`
+/// - `sitemap_options`: `SitemapOptions`, used to build the links to each blog
+/// - `options`: `FeedOptions` for configuration
+/// - `blog_options`: `BlogOptions`, used to decide which blogs are included
+pub fn create_rss_feed<T: AsRef<Path>>(
+    blog_root: T,
+    url_base: &String,
+    sitemap_options: &SitemapOptions,
+    options: &FeedOptions,
+    blog_options: &BlogOptions,
+) -> Result<String, BlogError> {
+    let (entries, _): (Vec<LowBlogEntry>, Vec<Tag>) =
+        parse_blogs(blog_root, None, None, blog_options)?;
+
+    let channel = Channel::blog(url_base, sitemap_options, options);
+
+    return create_rss_feed_inner(&entries, &channel, url_base, sitemap_options, options);
+}
+
+/// Use this function in `low` mode to generate an RSS 2.0 feed containing only
+/// the blogs with the specified tag. The feed belongs at
+/// `{url_base}/{tag_root_slug}/{tag_slug}/{rss_file_name}`
+///
+/// Parameters
+///
+/// - `blog_root`: Path to the root of the blog e.g. `files/blog`
+/// - `tag`: The tag to generate the feed for, either its name or its slug
+/// - `url_base`: URL of the website e.g. `www.example.com`
+/// - `sitemap_options`: `SitemapOptions`, used to build the links to each blog
+/// - `options`: `FeedOptions` for configuration
+/// - `blog_options`: `BlogOptions`, used to decide which blogs are included
+pub fn create_tag_rss_feed<T: AsRef<Path>>(
+    blog_root: T,
+    tag: &str,
+    url_base: &String,
+    sitemap_options: &SitemapOptions,
+    options: &FeedOptions,
+    blog_options: &BlogOptions,
+) -> Result<String, BlogError> {
+    let (entries, tags): (Vec<LowBlogEntry>, Vec<Tag>) =
+        parse_blogs(blog_root, None, None, blog_options)?;
+
+    let tag = find_tag(tag, &tags, blog_options);
+
+    let channel = Channel::tag(&tag, url_base, sitemap_options, options);
+
+    return create_rss_feed_inner(&entries, &channel, url_base, sitemap_options, options);
+}
+
+pub fn create_rss_feed_inner<T: Blog>(
+    entries: &[T],
+    channel: &Channel,
+    url_base: &String,
+    sitemap_options: &SitemapOptions,
+    options: &FeedOptions,
+) -> Result<String, BlogError> {
+    let mut buffer = Cursor::new(Vec::new());
+    let mut writer = EmitterConfig::new()
+        .perform_indent(true)
+        .create_writer(&mut buffer);
+
+    let entries = feed_entries(entries, channel, options);
+
+    writer
+        .write(
+            XmlEvent::start_element("rss")
+                .attr("version", "2.0")
+                .ns("atom", "http://www.w3.org/2005/Atom"),
+        )
+        .unwrap();
+    writer.write(XmlEvent::start_element("channel")).unwrap();
+
+    write_text_element(&mut writer, "title", &channel.title);
+    write_text_element(&mut writer, "link", &channel.home_link);
+    write_text_element(&mut writer, "description", &options.description);
+
+    writer
+        .write(
+            XmlEvent::start_element("atom:link")
+                .attr("href", &channel.feed_link(&options.rss_file_name))
+                .attr("rel", "self")
+                .attr("type", "application/rss+xml"),
+        )
+        .unwrap();
+    writer.write(XmlEvent::end_element()).unwrap();
+
+    if let Some(language) = &options.language {
+        write_text_element(&mut writer, "language", language);
+    }
+
+    // The newest blog is first, so that is when the feed last changed
+    if let Some(newest) = entries.first() {
+        let last_build = match newest.get_last_modified() {
+            Some(x) => x,
+            None => newest.get_date_listed(),
+        };
+
+        write_text_element(
+            &mut writer,
+            "lastBuildDate",
+            &date_time(last_build).to_rfc2822(),
+        );
+    }
+
+    for blog in entries {
+        writer.write(XmlEvent::start_element("item")).unwrap();
+
+        let link = blog_link(blog, url_base, sitemap_options);
+
+        write_text_element(&mut writer, "title", &blog.get_title());
+        write_text_element(&mut writer, "link", &link);
+        write_text_element(&mut writer, "description", &item_content(blog, options));
+
+        writer
+            .write(XmlEvent::start_element("guid").attr("isPermaLink", "true"))
+            .unwrap();
+        writer.write(XmlEvent::characters(&link)).unwrap();
+        writer.write(XmlEvent::end_element()).unwrap();
+
+        write_text_element(
+            &mut writer,
+            "pubDate",
+            &date_time(blog.get_date_listed()).to_rfc2822(),
+        );
+
+        for tag in blog.get_tags() {
+            write_text_element(&mut writer, "category", &tag);
+        }
+
+        writer.write(XmlEvent::end_element()).unwrap(); // Finish <item>
+    }
+
+    writer.write(XmlEvent::end_element()).unwrap(); // End <channel>
+    writer.write(XmlEvent::end_element()).unwrap(); // End <rss>
+
+    return Ok(String::from_utf8(buffer.into_inner()).unwrap());
+}
@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+
+/// Options to configure how feeds are generated
+///
+/// `blog-tools` generates a feed containing the most recent blogs. The links
+/// inside the feed are built in the same way as the sitemap, using the
+/// `blog_root_slug` from the `SitemapOptions`
+pub struct FeedOptions {
+    /// Title of the feed. The default is `Blog`
+    pub title: String,
+    /// Short description of the feed. The default is an empty string
+    pub description: String,
+    /// Optional language of the feed e.g. `en-gb`
+    pub language: Option<String>,
+    /// Optional author of the feed. In the Atom feed this is used for any blog
+    /// which does not have an `author_name` of its own, and if it is `None`
+    /// the title of the feed is used instead, as Atom requires an author
+    pub author_name: Option<String>,
+    /// The maximum number of blogs to include in the feed, newest first. If
+    /// this is `None` then every blog is included. The default is 20
+    pub limit: Option<usize>,
+    /// Whether each item should contain the full HTML of the blog. If `false`
+    /// (default behaviour) then the preview is used instead. In `medium` mode
+    /// setting this to `true` means the blogs in the feed are rendered once
+    /// when the feed is generated
+    pub full_html: bool,
+    /// Whether to generate a feed for every tag as well. Set this to `true` if
+    /// you want readers to be able to follow a single tag. The default is
+    /// `false`. Tag feeds are found underneath the `tag_root_slug` of the
+    /// `SitemapOptions` e.g. `www.example.com/blog/tag/science/feed.xml`
+    pub include_tag_feeds: bool,
+    /// The file name the RSS feed is served from, relative to the
+    /// `blog_root_slug` of the `SitemapOptions`. The default is `feed.xml`,
+    /// meaning the feed is found at `www.example.com/blog/feed.xml`. This is
+    /// used for the self link of the RSS feed
+    pub rss_file_name: String,
+    /// The file name the Atom feed is served from, relative to the
+    /// `blog_root_slug` of the `SitemapOptions`. The default is `atom.xml`,
+    /// meaning the feed is found at `www.example.com/blog/atom.xml`. This is
+    /// used for the self link of the Atom feed
+    pub atom_file_name: String,
+    /// The file name the JSON Feed is served from, relative to the
+    /// `blog_root_slug` of the `SitemapOptions`. The default is `feed.json`,
+    /// meaning the feed is found at `www.example.com/blog/feed.json`
+    pub json_file_name: String,
+}
+
+impl Default for FeedOptions {
+    fn default() -> Self {
+        Self {
+            title: "Blog".to_string(),
+            description: String::new(),
+            language: None,
+            author_name: None,
+            limit: Some(20),
+            full_html: false,
+            include_tag_feeds: false,
+            rss_file_name: "feed.xml".to_string(),
+            atom_file_name: "atom.xml".to_string(),
+            json_file_name: "feed.json".to_string(),
+        }
+    }
+}
+
+/// A JSON Feed, as described by <https://jsonfeed.org/version/1.1>
+///
+/// `blog-tools` generates these as a `String`, but this type can be used to
+/// deserialize the feed again if you need to modify it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JsonFeed {
+    /// Always `https://jsonfeed.org/version/1.1`
+    pub version: String,
+    /// Title of the feed
+    pub title: String,
+    /// URL of the blog index
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub home_page_url: Option<String>,
+    /// URL of the feed itself
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feed_url: Option<String>,
+    /// Short description of the feed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Language of the feed e.g. `en-gb`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Authors of the feed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authors: Option<Vec<JsonFeedAuthor>>,
+    /// The blogs in the feed, newest first
+    pub items: Vec<JsonFeedItem>,
+}
+
+/// A single blog in a `JsonFeed`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JsonFeedItem {
+    /// Unique id of the blog, which is its URL
+    pub id: String,
+    /// URL of the blog
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Title of the blog
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Full HTML of the blog, present when `full_html` is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_html: Option<String>,
+    /// Plain text preview of the blog, present when `full_html` is not set.
+    /// Unlike the other feeds, this is not HTML escaped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_text: Option<String>,
+    /// The description of the blog
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Publication date in RFC 3339 format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date_published: Option<String>,
+    /// Last modified date in RFC 3339 format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date_modified: Option<String>,
+    /// Authors of the blog
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authors: Option<Vec<JsonFeedAuthor>>,
+    /// Tags of the blog
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+/// An author in a `JsonFeed`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JsonFeedAuthor {
+    /// Name of the author
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// URL of the author's webpage
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
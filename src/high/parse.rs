@@ -1,11 +1,17 @@
-use std::{collections::HashMap, path::Path};
+use std::{cmp::Reverse, collections::HashMap, path::Path};
 
 use markdown::mdast::Node;
 
 use crate::{
+    archive::build_archive,
     common::{parse_blogs, BlogError},
+    feed::{create_feeds_inner, FeedOptions},
+    navigation::positions,
+    related::related_index,
+    search::{build_index, html_text},
     sitemap::{create_sitemap_inner, SitemapOptions},
-    types::Blog,
+    tags::{tag_index, Tag},
+    types::{Blog, BlogOptions},
 };
 
 use super::types::{HighBlog, HighBlogEntry};
@@ -13,15 +19,21 @@ use super::types::{HighBlog, HighBlogEntry};
 /// Gets the whole `HighBlog` from the specified path. Useful to combine with lazy
 /// static for loading times
 ///
-/// The path should be a folder which contains markdown files next to json files
+/// The path should be a folder which contains markdown files next to json files,
+/// or markdown files with front matter
 ///
 /// Add an optional toc generation function if you want a table of contents
 ///
 /// Optionally specify the number of chars for the preview. Default is 320.
 ///
+/// `sitemap_options` and `feed_options` configure the sitemap and the feeds
+///
+/// `blog_options` configures which blogs are included e.g. whether drafts are
+/// shown
+///
 /// ```rust,ignore
 /// pub static BLOG_ROOT: &str = "examples/blog/post";
-
+///
 /// lazy_static! {
 ///     pub static ref STATIC_BLOG_ENTRIES: Blog = get_blog(PathBuf::from(BLOG_ROOT), None, None).unwrap();
 /// }
@@ -33,6 +45,8 @@ pub fn get_high_blog<T: AsRef<Path>>(
     preview_chars: Option<usize>,
     url: &String,
     sitemap_options: &SitemapOptions,
+    feed_options: &FeedOptions,
+    blog_options: &BlogOptions,
 ) -> Result<HighBlog, BlogError> {
     return get_blog_entries(
         base,
@@ -40,6 +54,8 @@ pub fn get_high_blog<T: AsRef<Path>>(
         preview_chars,
         url,
         sitemap_options,
+        feed_options,
+        blog_options,
     );
 }
 
@@ -49,24 +65,61 @@ fn get_blog_entries<T: AsRef<Path>>(
     preview_chars: Option<usize>,
     url: &String,
     sitemap_options: &SitemapOptions,
+    feed_options: &FeedOptions,
+    blog_options: &BlogOptions,
 ) -> Result<HighBlog, BlogError> {
     let mut hashes: HashMap<String, HighBlogEntry> = HashMap::new();
 
-    let (mut entries, tags): (Vec<HighBlogEntry>, Vec<String>) =
-        parse_blogs(base, toc_generation_func, preview_chars)?;
+    let (mut entries, tags): (Vec<HighBlogEntry>, Vec<Tag>) =
+        parse_blogs(base, toc_generation_func, preview_chars, blog_options)?;
 
     for entry in &entries {
         hashes.insert(entry.get_full_slug(), entry.clone());
     }
 
-    entries.sort_by(|a, b| b.get_date_listed().cmp(&a.get_date_listed()));
+    entries.sort_by_key(|x| Reverse(x.get_date_listed()));
+
+    let tag_index = tag_index(&entries);
+
+    let positions = positions(&entries);
+
+    let archive = build_archive(&entries);
+
+    let texts: Vec<String> = if blog_options.search.is_some() || blog_options.related.is_some() {
+        entries.iter().map(|x| html_text(&x.get_html())).collect()
+    } else {
+        vec![]
+    };
+
+    let search_index = blog_options
+        .search
+        .as_ref()
+        .map(|x| build_index(&entries, &texts, x));
+
+    let related = match &blog_options.related {
+        Some(x) => related_index(&entries, &texts, x),
+        None => HashMap::new(),
+    };
 
     let sitemap = create_sitemap_inner(&entries, Some(&tags), url, sitemap_options)?;
 
+    let feeds = create_feeds_inner(&entries, &tags, url, sitemap_options, feed_options)?;
+
     return Ok(HighBlog {
         hash: hashes,
         entries: entries,
         tags: tags,
+        tag_index: tag_index,
+        positions: positions,
+        archive: archive,
+        search_index: search_index,
+        related: related,
         sitemap: sitemap,
+        feed: feeds.rss,
+        atom_feed: feeds.atom,
+        json_feed: feeds.json,
+        tag_feeds: feeds.tag_rss,
+        tag_atom_feeds: feeds.tag_atom,
+        tag_json_feeds: feeds.tag_json,
     });
 }
@@ -0,0 +1,190 @@
+use std::{
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::common::{get_blog_paths, BlogError};
+
+use super::types::BlogLayout;
+
+/// The default layout, organised by year and then by date
+///
+/// - blog
+///     - 2023
+///         - 2023-01-01
+///             - my_first_blog.json
+///             - my_first_blog.md
+///         - (other folders)
+pub struct DatedLayout;
+
+impl BlogLayout for DatedLayout {
+    fn blog_paths(&self, base: &Path) -> Result<Vec<PathBuf>, BlogError> {
+        return get_blog_paths(base, None);
+    }
+
+    fn candidate_paths(
+        &self,
+        base: &Path,
+        date: NaiveDate,
+        slug: &str,
+    ) -> Result<Vec<PathBuf>, BlogError> {
+        let folder = base
+            .join(format!("{}", date.year()))
+            .join(format!("{}", date));
+
+        if !folder.is_dir() {
+            return Ok(vec![]);
+        }
+
+        return Ok(named_first(get_blog_paths(folder, None)?, slug));
+    }
+
+    fn blog_month(&self, _base: &Path, path: &Path) -> Option<(i32, u32)> {
+        // The folder the blog is in is named after its date
+        let folder = path.parent()?.file_name()?.to_str()?;
+
+        return match folder.parse::<NaiveDate>() {
+            Ok(x) => Some((x.year(), x.month())),
+            Err(_) => None,
+        };
+    }
+
+    fn date_folders(&self, base: &Path) -> Result<Option<Vec<(NaiveDate, PathBuf)>>, BlogError> {
+        let mut folders = vec![];
+
+        for year in sub_folders(base)? {
+            for folder in sub_folders(&year)? {
+                let name = match folder.file_name().and_then(|x| x.to_str()) {
+                    Some(x) => x,
+                    None => continue,
+                };
+
+                if let Ok(x) = name.parse::<NaiveDate>() {
+                    folders.push((x, folder));
+                }
+            }
+        }
+
+        folders.sort();
+
+        return Ok(Some(folders));
+    }
+}
+
+// Every folder directly inside `path`
+fn sub_folders(path: &Path) -> Result<Vec<PathBuf>, BlogError> {
+    if !path.is_dir() {
+        return Err(BlogError::NotADirectory(path.to_path_buf()));
+    }
+
+    let entries = match fs::read_dir(path) {
+        Ok(x) => x,
+        Err(y) => return Err(BlogError::File(y)),
+    };
+
+    let mut folders = vec![];
+
+    for entry in entries {
+        let entry = match entry {
+            Ok(x) => x,
+            Err(y) => return Err(BlogError::File(y)),
+        };
+
+        if entry.path().is_dir() {
+            folders.push(entry.path());
+        }
+    }
+
+    return Ok(folders);
+}
+
+// Files named after the slug come first, as they are almost always the blog.
+// The others are still candidates, as the slug comes from the metadata
+fn named_first(mut paths: Vec<PathBuf>, slug: &str) -> Vec<PathBuf> {
+    paths.sort_by_key(|x| x.file_stem() != Some(OsStr::new(slug)));
+
+    return paths;
+}
+
+/// Every blog is directly inside the root, with no sub folders
+///
+/// - blog
+///     - my_first_blog.json
+///     - my_first_blog.md
+///     - (other blogs)
+pub struct FlatLayout;
+
+impl BlogLayout for FlatLayout {
+    fn blog_paths(&self, base: &Path) -> Result<Vec<PathBuf>, BlogError> {
+        return get_blog_paths(base, Some(1));
+    }
+
+    fn candidate_paths(
+        &self,
+        base: &Path,
+        _date: NaiveDate,
+        slug: &str,
+    ) -> Result<Vec<PathBuf>, BlogError> {
+        return Ok(named_first(get_blog_paths(base, Some(1))?, slug));
+    }
+}
+
+/// Every blog has its own folder, named after its slug and organised by year
+/// and month, with the markdown in an `index.md`. Anything else in the folder
+/// (e.g. images) is ignored
+///
+/// - blog
+///     - 2023
+///         - 01
+///             - first-blog
+///                 - index.json
+///                 - index.md
+///                 - cover.png
+///         - (other folders)
+pub struct PageBundleLayout;
+
+impl BlogLayout for PageBundleLayout {
+    fn blog_paths(&self, base: &Path) -> Result<Vec<PathBuf>, BlogError> {
+        let mut paths = get_blog_paths(base, None)?;
+
+        paths.retain(|x| x.file_name() == Some(OsStr::new("index.md")));
+
+        return Ok(paths);
+    }
+
+    fn candidate_paths(
+        &self,
+        base: &Path,
+        date: NaiveDate,
+        slug: &str,
+    ) -> Result<Vec<PathBuf>, BlogError> {
+        let path = base
+            .join(format!("{}", date.year()))
+            .join(format!("{:02}", date.month()))
+            .join(slug)
+            .join("index.md");
+
+        return if path.is_file() {
+            Ok(vec![path])
+        } else {
+            Ok(vec![])
+        };
+    }
+
+    fn blog_month(&self, base: &Path, path: &Path) -> Option<(i32, u32)> {
+        // The path is `{year}/{month}/{slug}/index.md`
+        let relative = path.strip_prefix(base).ok()?;
+        let mut parts = relative.iter().map(|x| x.to_str());
+
+        let year = parts.next()??.parse::<i32>().ok()?;
+        let month = parts.next()??.parse::<u32>().ok()?;
+
+        return match month {
+            1..=12 => Some((year, month)),
+            _ => None,
+        };
+    }
+}
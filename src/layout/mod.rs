@@ -0,0 +1,4 @@
+mod layouts;
+mod types;
+pub use layouts::{DatedLayout, FlatLayout, PageBundleLayout};
+pub use types::BlogLayout;
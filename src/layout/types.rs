@@ -0,0 +1,41 @@
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+
+use crate::common::BlogError;
+
+/// Describes how the blog is laid out on disc. Every mode uses this to find
+/// blogs, so implement this trait if none of the built in layouts suit your
+/// blog
+///
+/// Note that the slug of a blog always comes from its metadata, never from
+/// the path - the layout only needs to know where to look
+pub trait BlogLayout {
+    /// Find the markdown file of every blog underneath `base`
+    fn blog_paths(&self, base: &Path) -> Result<Vec<PathBuf>, BlogError>;
+    /// Find the markdown files which could be the blog with this date and
+    /// slug. Each candidate is checked against its metadata, so it is fine to
+    /// return more files than necessary, but the blog must be among them.
+    /// Candidates are checked in order, so put the most likely first
+    fn candidate_paths(
+        &self,
+        base: &Path,
+        date: NaiveDate,
+        slug: &str,
+    ) -> Result<Vec<PathBuf>, BlogError>;
+    /// The year and month of the blog at `path`, if the layout can tell from
+    /// the path alone. This lets `low::get_archive` count the blogs without
+    /// reading any of them when `from_paths` is set. The default is `None`, meaning the metadata of
+    /// the blog is read instead
+    fn blog_month(&self, _base: &Path, _path: &Path) -> Option<(i32, u32)> {
+        return None;
+    }
+    /// The folder of every date with blogs, oldest first, if the layout keeps
+    /// the blogs of each date in their own folder. This lets
+    /// `low::get_neighbour_blogs` find the blogs either side of a blog by only
+    /// reading the blogs in the folders nearest to it. The default is `None`,
+    /// meaning every blog is read instead
+    fn date_folders(&self, _base: &Path) -> Result<Option<Vec<(NaiveDate, PathBuf)>>, BlogError> {
+        return Ok(None);
+    }
+}
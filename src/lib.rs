@@ -13,7 +13,7 @@
 //!         - (other folders)
 //!
 //! That is, organised by year, organised by date (yyyy-mm-dd), then a `blog.md`
-//! next to a `blog.json`.
+//! next to a `blog.json`. Only files ending in `.md` or `.html` are read as blogs.
 //!
 //! The JSON must conform to the following schema
 //!
@@ -29,10 +29,224 @@
 //! "author_name": Optional<String>,
 //! "author_webpage": Optional<String>,
 //! "last_modified": Optional<Date>, (ISO 8601)
-//! "priority": Optional<float>
+//! "priority": Optional<float>,
+//! "draft": Optional<bool>,
+//! "publish_at": Optional<DateTime>, (ISO 8601, UTC if no offset is given)
+//! "safety": Optional<"trusted" | "safe" | "sanitized">
 //! }
 //! ```
 //!
+//! ## Front Matter
+//!
+//! Instead of a sidecar JSON, the same metadata can be placed at the top of the
+//! markdown file as YAML front matter fenced by `---`, or as TOML front matter
+//! fenced by `+++`. The front matter is stripped before the markdown is rendered
+//!
+//! ```rust,ignore
+//! ---
+//! title: My First Blog
+//! date: 2023-01-01
+//! slug: first-blog
+//! tags: [philosophy, science]
+//! ---
+//!
+//! # My First Blog
+//! ```
+//!
+//! If a post has both front matter and a sidecar JSON, the front matter wins and
+//! the JSON is ignored entirely - the two are never merged
+//!
+//! ## Extra Metadata
+//!
+//! Any other fields in the JSON or front matter, such as `cover_image`, `series` or
+//! `lang`, are kept rather than dropped. Every blog entry has them in an `extra`
+//! map, so in a template they are available as e.g. `blog.extra.cover_image`. In
+//! Rust, use `get_extra`, or `get_extra_as` to deserialize them into your own type
+//!
+//! ## Drafts and Scheduled Posts
+//!
+//! A blog with `"draft": true` is left out of everything - the entries, the tags,
+//! the sitemap and the feeds. A blog with a `publish_at` in the future is treated
+//! the same way until that time has passed. To see these blogs on a local preview
+//! server, set `include_drafts` in the `BlogOptions`
+//!
+//! ## Layouts
+//!
+//! The folder structure above is the default `DatedLayout`. Set the `layout` in the
+//! `BlogOptions` to `FlatLayout` to keep every blog directly inside the root, or to
+//! `PageBundleLayout` to give every blog its own `year/month/slug/index.md` folder.
+//! For anything else, implement the `BlogLayout` trait
+//!
+//! ## Markdown Dialects
+//!
+//! By default blogs are rendered as plain CommonMark. Set the `dialect` of the
+//! `render` options in the `BlogOptions` to `MarkdownDialect::Gfm` for GitHub
+//! flavoured markdown, with tables, footnotes, strikethrough and task lists. For
+//! anything else, such as `$math$`, use `MarkdownDialect::Custom` with your own
+//! combination of the `markdown` crate's `ParseOptions` and `CompileOptions`
+//!
+//! ## Safe Rendering
+//!
+//! By default the HTML in blogs is trusted - raw HTML and `javascript:` links are
+//! rendered as they are. When publishing blogs written by other people, set the
+//! `safety` of the `render` options in the `BlogOptions` to `HtmlSafety::Safe` to
+//! escape raw HTML and remove dangerous links, or to `HtmlSafety::Sanitized` to
+//! allow raw HTML but clean the rendered HTML so only the tags, attributes and URL
+//! schemes in the `SanitizeOptions` are kept. A single blog can make this stricter
+//! with `"safety": "sanitized"` or `"safe"` in its metadata, from `trusted` to
+//! `sanitized` to `safe`, but never less strict - a guest author can't mark their
+//! own blog as trusted
+//!
+//! ## Syntax Highlighting
+//!
+//! With the `highlight` feature enabled, fenced code blocks can be highlighted while
+//! the blog is rendered, so no highlighter is needed in the browser. Set the
+//! `highlight` of the `render` options in the `BlogOptions` to some
+//! `HighlightOptions`. The colours are written either as inline styles, or as
+//! classes - in which case serve the stylesheet from `render::theme_css` alongside
+//! the blog
+//!
+//! ## Previews
+//!
+//! Every blog has a plain text preview of its first paragraphs, useful for index
+//! pages. Previews longer than the requested number of characters are cut at the
+//! end of a word, or of a sentence, and an ellipsis is added - configure this with
+//! the `preview` options in the `BlogOptions`. To choose the preview yourself, put
+//! an `<!-- more -->` line in the markdown and everything above it becomes the
+//! preview. A marker inside a code block is left alone. The preview is HTML
+//! escaped, so it is safe to include in a template as it is
+//!
+//! Set `excerpt_blocks` in the `preview` options to also keep an HTML excerpt of
+//! the first few paragraphs, lists and so on, with the links and emphasis left in
+//! place. This is available as `excerpt`
+//!
+//! ## Reading Time
+//!
+//! Every blog has a `word_count` and a `reading_time` in minutes, rounded up. Code
+//! blocks and raw HTML are not counted, and in Chinese and Japanese each character
+//! counts as a word. The reading speed is 200 words per minute by default - change
+//! it with `words_per_minute` in the `BlogOptions`
+//!
+//! ## Pagination
+//!
+//! Split the blogs into pages for index pages and tag pages with `get_page` and
+//! `get_tag_page` in `high` and `medium`, or `preview_blogs_page` and
+//! `preview_blogs_tagged_page` in `low`, configured with `PaginationOptions`. Pages
+//! start from 1, and each `Page` has the total number of pages, whether there is a
+//! next or previous page, and the URLs of these pages - e.g. `/blog/page/2`, made
+//! from the `blog_root_slug` or `tag_root_slug` of the `SitemapOptions`. Set
+//! `pagination` in the `SitemapOptions` to include these pages in the sitemap
+//!
+//! ## Tags
+//!
+//! Every tag has a name and a slug, used in its URLs. Tags with the same slug are
+//! the same tag, so `Machine Learning` and `machine-learning` are one tag, with
+//! the slug `machine-learning`. Change how slugs are made with the `slug_style` in
+//! the `tags` options of the `BlogOptions`, and list other ways of writing a tag
+//! as its `aliases`, or read these from a file with `read_tag_aliases`
+//!
+//! ```yaml
+//! Machine Learning: [ML]
+//! ```
+//!
+//! The `tags` of `high` and `medium`, and `get_blog_tag_list` in `low`, give the
+//! name and slug of every tag. Each blog has `tag_slugs` alongside its `tags`, and
+//! the sitemap, the tag feeds and the tag pages all use the slug
+//!
+//! `high` and `medium` keep a `tag_index` from the slug of each tag to the slugs
+//! of its blogs, newest first, so tag pages don't need to search every blog.
+//! `get_tag_counts` gives the number of blogs with each tag, from most to least
+//! used, and `get_tag_cloud` weights every tag from 1 up to the `levels` of the
+//! `TagCloudOptions` for a tag cloud. In `low`, `get_tag_counts` reads only the
+//! metadata, and `tag_cloud` weights the counts
+//!
+//! ## Archive
+//!
+//! `high` and `medium` keep an `archive` of the blogs grouped by year and then by
+//! month, newest first, with the number of blogs in each and the slugs of the
+//! blogs in each month. In `low`, `get_archive` gives the same grouping. With
+//! `from_paths` set and the default `DatedLayout`, this comes from the
+//! `year/yyyy-mm-dd` folders alone, so no blog is read - but this means **drafts
+//! and scheduled blogs are counted too**. Set `include_archive` in the
+//! `SitemapOptions` to include a page for every year and month in the sitemap e.g.
+//! `www.example.com/blog/archive/2024/03`
+//!
+//! ## Search
+//!
+//! Set `search` in the `BlogOptions` to `Some(SearchOptions::default())` and
+//! `high` and `medium` build a full text search index of the titles, tags,
+//! keywords and text of every blog. `search` then gives the best matches first,
+//! ranked with BM25, each with a snippet of the blog with the matching words
+//! highlighted in `<mark>`. Every word of a query must be found in a blog for it
+//! to match. Put words in quotes to match them as a phrase e.g. `"static site"`,
+//! and end a word with `*` to match every word starting with it e.g. `optim*`.
+//! The index holds the text of every blog, so it is off by default
+//!
+//! ## Static Search
+//!
+//! For a blog deployed without a server, `export_search` in `high`, `medium` and
+//! `low` exports a search index to search in the browser instead. This is a
+//! `search.json` with the id, URL, title, date, tags, preview and words of every
+//! blog, which can be given as it is to client side search libraries such as Lunr,
+//! MiniSearch or Fuse.js. Set `shard_size` in the `SearchExportOptions` to split a
+//! large index into shards, which `search.json` then lists. The format is
+//! described in full on `SearchExport`
+//!
+//! ## Related Posts
+//!
+//! Set `related` in the `BlogOptions` to `Some(RelatedOptions::default())` and
+//! `high` and `medium` find the related blogs of every blog, for a "you might also
+//! like" section. Every other blog is scored by the tags and keywords it shares
+//! with the blog, and by how similar their text is using TF-IDF, and the best few
+//! are kept in `related`. `get_related` gives the related blogs themselves. In
+//! `low`, `get_related_blogs` finds the related blogs of a single blog when it is
+//! needed, which means reading every blog
+//!
+//! ## Previous and Next
+//!
+//! `get_neighbours` in `high` and `medium` gives the blogs either side of a blog,
+//! found by its full slug, for previous and next links. `prev` is the blog
+//! published just before and `next` the blog published just after. Give a tag to
+//! only link between the blogs with that tag. In `low`, `get_neighbour_blogs` does
+//! the same - with the default `DatedLayout` only the date folders nearest to the
+//! blog are read, rather than every blog
+//!
+//! ## Queries
+//!
+//! `query` in `high` and `medium` starts a `Query` over the blogs, to filter and
+//! sort them without copying any. Filter on tags with `all_tags`, `any_tags` and
+//! `none_tags`, on the author, on a range of dates with `from` and `until`, on
+//! keywords, or on anything else with `filter`. Sort by the date, the last
+//! modified date, the title or the priority with `sort_by`, then get every match
+//! with `results` or a single page of them with `page`
+//!
+//! ```rust,ignore
+//! let blogs = STATIC_BLOG_ENTRIES
+//!     .query()
+//!     .author("Indigo Curnick")
+//!     .from(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+//!     .until(NaiveDate::from_ymd_opt(2024, 12, 31).unwrap())
+//!     .any_tags(&["rust", "wasm"])
+//!     .sort_by(SortBy::LastModified, SortOrder::Descending)
+//!     .results();
+//! ```
+//!
+//! ## Table of Contents
+//!
+//! Every markdown heading in the rendered HTML is given an `id`, made from its text
+//! in the same way as GitHub. Headings written in raw HTML are left as they are.
+//! Pass `toc::generate_toc` as the `toc_generation_func` to get a table of contents
+//! linking to them. For a table of contents with only some heading levels, or for a
+//! structured tree of the headings rather than HTML, use `toc::get_toc` with
+//! `TocOptions`
+//!
+//! ## Checking a Blog
+//!
+//! `check::check_blog` reads every blog and returns a report of every problem it
+//! finds - malformed JSON or front matter, missing files, bad dates, priorities
+//! outside of 0.0 to 1.0 and duplicate slugs - along with the file each was found
+//! in. This is useful to run in CI before deploying
+//!
 //! ## Slugs
 //!
 //! In `blog-tools` all slugs are /{date}/{sub-slug}.
@@ -57,7 +271,7 @@
 //! ```rust,ignore
 //! lazy_static! {
 //!    pub static ref STATIC_BLOG_ENTRIES: HighBlog =
-//!        get_high_blog(PathBuf::from(BLOG_ROOT), None, None, URL, &SitemapOptions::default());
+//!        get_high_blog(PathBuf::from(BLOG_ROOT), None, None, URL, &SitemapOptions::default(), &FeedOptions::default(), &BlogOptions::default());
 //!    }
 //! ```
 //!
@@ -67,7 +281,7 @@
 //! ```rust,ignore
 //! lazy_static! {
 //!    pub static ref STATIC_BLOG_ENTRIES: MediumBlog =
-//!        get_medium_blog(PathBuf::from(BLOG_ROOT), None, None, URL, &SitemapOptions::default());
+//!        get_medium_blog(PathBuf::from(BLOG_ROOT), None, None, URL, &SitemapOptions::default(), &FeedOptions::default(), &BlogOptions::default());
 //!     }
 //!
 //! let this_blog = match all_blogs.hash.get(&complete_slug) {
@@ -78,7 +292,7 @@
 //! context.insert(
 //!     "blog",
 //!     &this_blog
-//!         .render(PathBuf::from_str(BLOG_ROOT).unwrap())
+//!         .render(PathBuf::from_str(BLOG_ROOT).unwrap(), &BlogOptions::default())
 //!         .unwrap(),
 //! );
 //! ```
@@ -86,10 +300,11 @@
 //! everything at runtime.
 //!
 //! ```rust,ignore
-//! let preview = preview_blogs(PathBuf::from_str(BLOG_ROOT).unwrap(), 2, None);
-//! let tags = get_blog_tag_list(PathBuf::from_str(BLOG_ROOT).unwrap());
-//! let blog_post = render_blog_post(PathBuf::from_str(BLOG_ROOT).unwrap(), date, slug, None).unwrap();
-//! let sitemap = create_sitemap(BLOG_ROOT, URL, &SitemapOptions::default());
+//! let preview = preview_blogs(PathBuf::from_str(BLOG_ROOT).unwrap(), 2, None, &BlogOptions::default());
+//! let tags = get_blog_tag_list(PathBuf::from_str(BLOG_ROOT).unwrap(), &BlogOptions::default());
+//! let blog_post = render_blog_post(PathBuf::from_str(BLOG_ROOT).unwrap(), date, slug, None, &BlogOptions::default()).unwrap();
+//! let sitemap = create_sitemap(BLOG_ROOT, URL, &SitemapOptions::default(), &BlogOptions::default());
+//! let feed = create_rss_feed(BLOG_ROOT, URL, &SitemapOptions::default(), &FeedOptions::default(), &BlogOptions::default());
 //! ```
 //!
 //! This method can have serious runtime performance implecations, but might be
@@ -110,12 +325,18 @@
 //! You can then view the blog from localhost:8080
 //!
 #![warn(missing_docs)]
+#![allow(clippy::needless_return, clippy::redundant_field_names)]
+#![allow(clippy::module_inception)]
 
 mod common;
 
 mod types;
 
-pub use types::Blog;
+#[cfg(test)]
+mod testing;
+
+pub use common::BlogError;
+pub use types::{Blog, BlogOptions};
 
 /// Sitemap related utilities can be found here. If you use `high` or `medium`
 /// then the only thing you need from here is `SitemapOptions` to configure
@@ -125,6 +346,72 @@ pub use types::Blog;
 /// generate the sitemap
 pub mod sitemap;
 
+/// Feed related utilities can be found here. If you use `high` or `medium`
+/// then the only thing you need from here is `FeedOptions` to configure
+/// how the feeds are generated.
+///
+/// If you use `low` then the functions `create_rss_feed`, `create_atom_feed`
+/// and `create_json_feed` can be used to generate an RSS, Atom or JSON Feed.
+/// The `create_tag_*` variants generate the same feeds for a single tag
+pub mod feed;
+
+/// Layouts describe how the blog is organised on disc. The default is
+/// `DatedLayout`, organised by year and then by date. Select a different
+/// layout with the `layout` field of `BlogOptions`, or implement `BlogLayout`
+/// for your own
+pub mod layout;
+
+/// Checks a blog for problems. `check_blog` reads every blog and reports
+/// everything wrong with them at once, rather than stopping at the first
+/// error like the other modules do
+pub mod check;
+
+/// Options for rendering the markdown of every blog, such as which dialect of
+/// markdown to use. Set these with the `render` field of `BlogOptions`
+pub mod render;
+
+/// Options for the plain text previews of every blog. Set these with the
+/// `preview` field of `BlogOptions`
+pub mod preview;
+
+/// The built in table of contents generator. Pass `generate_toc` as the
+/// `toc_generation_func`, or use `get_toc` directly for a structured tree of
+/// the headings. The links match the `id`s given to every rendered heading
+pub mod toc;
+
+/// Splits the blogs into pages, for index pages and tag pages. Use `get_page`
+/// and `get_tag_page` in `high` and `medium`, or `preview_blogs_page` and
+/// `preview_blogs_tagged_page` in `low`
+pub mod pagination;
+
+/// Normalises, counts and weights the tags of the blog. Configure how tags are
+/// normalised with `TagOptions`, set with the `tags` field of `BlogOptions`.
+/// Use `get_tag_counts` and `get_tag_cloud` in `high` and `medium`, or
+/// `get_tag_counts` and `tag_cloud` in `low`
+pub mod tags;
+
+/// Groups the blogs by year and month for an archive page. `high` and
+/// `medium` keep this as `archive`, and `low` has `get_archive`
+pub mod archive;
+
+/// Full text search of the blogs. Set `search` in the `BlogOptions` to build a
+/// `SearchIndex` in `high` and `medium`, then use `search` to query it. Use
+/// `export_search` to export a `SearchExport` for searching in the browser
+pub mod search;
+
+/// Finds the related blogs of each blog, for a "you might also like" section.
+/// Set `related` in the `BlogOptions` to find them in `high` and `medium`, or
+/// use `get_related_blogs` in `low`
+pub mod related;
+
+/// Previous and next links between blogs. Use `get_neighbours` in `high` and
+/// `medium`, or `get_neighbour_blogs` in `low`
+pub mod navigation;
+
+/// Filters and sorts the blogs, such as every blog by an author in a year with
+/// one of a few tags. Start a `Query` with `query` in `high` or `medium`
+pub mod query;
+
 /// `high` refers to high RAM usage - using this module you will be effectively
 /// storing the entire blog in memory at all times using a lazy static. Highest
 /// runtime performance but higest RAM usage
@@ -132,7 +419,7 @@ pub mod sitemap;
 /// ```rust,ignore
 /// lazy_static! {
 ///     pub static ref STATIC_BLOG_ENTRIES: HighBlog =
-///         get_high_blog(PathBuf::from(BLOG_ROOT), None, None, URL, &SitemapOptions::default());
+///         get_high_blog(PathBuf::from(BLOG_ROOT), None, None, URL, &SitemapOptions::default(), &FeedOptions::default(), &BlogOptions::default());
 /// }
 /// ```
 pub mod high;
@@ -144,10 +431,11 @@ pub mod high;
 /// of database
 ///
 /// ```rust,ignore
-/// let preview = preview_blogs(PathBuf::from_str(BLOG_ROOT).unwrap(), 2, None);
-/// let tags = get_blog_tag_list(PathBuf::from_str(BLOG_ROOT).unwrap());
-/// let blog_post = render_blog_post(PathBuf::from_str(BLOG_ROOT).unwrap(), date, slug, None).unwrap();
-/// let sitemap = create_sitemap(BLOG_ROOT, URL, &SitemapOptions::default());
+/// let preview = preview_blogs(PathBuf::from_str(BLOG_ROOT).unwrap(), 2, None, &BlogOptions::default());
+/// let tags = get_blog_tag_list(PathBuf::from_str(BLOG_ROOT).unwrap(), &BlogOptions::default());
+/// let blog_post = render_blog_post(PathBuf::from_str(BLOG_ROOT).unwrap(), date, slug, None, &BlogOptions::default()).unwrap();
+/// let sitemap = create_sitemap(BLOG_ROOT, URL, &SitemapOptions::default(), &BlogOptions::default());
+/// let feed = create_rss_feed(BLOG_ROOT, URL, &SitemapOptions::default(), &FeedOptions::default(), &BlogOptions::default());
 /// ```
 pub mod low;
 
@@ -158,7 +446,7 @@ pub mod low;
 /// ```rust,ignore
 /// lazy_static! {
 ///     pub static ref STATIC_BLOG_ENTRIES: MediumBlog =
-///         get_medium_blog(PathBuf::from(BLOG_ROOT), None, None, URL, &SitemapOptions::Default());
+///         get_medium_blog(PathBuf::from(BLOG_ROOT), None, None, URL, &SitemapOptions::Default(), &FeedOptions::default(), &BlogOptions::default());
 ///     }
 ///
 /// let this_blog = match all_blogs.hash.get(&complete_slug) {
@@ -169,7 +457,7 @@ pub mod low;
 /// context.insert(
 ///     "blog",
 ///     &this_blog
-///         .render(PathBuf::from_str(BLOG_ROOT).unwrap())
+///         .render(PathBuf::from_str(BLOG_ROOT).unwrap(), &BlogOptions::default())
 ///         .unwrap(),
 /// );
 /// ```
@@ -1,5 +1,9 @@
 mod parse;
 mod types;
 
-pub use parse::{get_blog_tag_list, preview_blogs, preview_blogs_tagged, render_blog_post};
+pub use parse::{
+    export_search, get_archive, get_blog_tag_list, get_neighbour_blogs, get_related_blogs,
+    get_tag_counts, preview_blogs, preview_blogs_page, preview_blogs_tagged,
+    preview_blogs_tagged_page, render_blog_post,
+};
 pub use types::{LowBlogEntry, PreviewBlogEntry};
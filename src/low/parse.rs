@@ -1,147 +1,172 @@
-use std::{fs, path::Path};
-
-use markdown::{mdast::Node, to_html_with_options, Options};
-use walkdir::WalkDir;
-
-use crate::common::{get_json_data, preview::get_preview, toc, BlogError};
+use std::{
+    cmp::Reverse,
+    path::{Path, PathBuf},
+};
+
+use chrono::{Datelike, NaiveDate};
+use markdown::mdast::Node;
+
+use crate::{
+    archive::{count_archive, ArchiveYear},
+    common::{
+        count_words, find_blog, get_blog_data, get_blog_paths, is_published, reading_time,
+        render_markdown, toc, BlogError, BlogJson,
+    },
+    navigation::Neighbours,
+    pagination::{new_page, total_pages, Page, PaginationOptions},
+    preview::{get_excerpt, get_preview},
+    related::{profile, top_related, RelatedOptions},
+    search::{export_documents, html_text, SearchExport, SearchExportOptions},
+    sitemap::SitemapOptions,
+    tags::{encode_slug, normalise_tag, sorted_counts, Tag, TagCount},
+    types::BlogOptions,
+};
 
 use super::types::{LowBlogEntry, PreviewBlogEntry};
 
-/// Use this function to get a list of all unique tags in your blog
+/// Use this function to get a list of all unique tags in your blog, each with
+/// its name and slug
 ///
 /// WARNING: With many blogs this function could become extremely slow -
 /// maybe consider caching this? Even though this is the no-cache option, a list
 /// of single word strings isn't that large. If even this is too large to fit in
 /// memory, you probably need a database rather than this crate
-pub fn get_blog_tag_list<T: AsRef<Path>>(base: T) -> Result<Vec<String>, BlogError> {
-    let mut tags = vec![];
-
-    for entry in WalkDir::new(base) {
-        let entry = entry.unwrap();
-
-        let path = entry.path();
+///
+/// `blog_options` configures which blogs are included e.g. whether drafts are
+/// shown
+pub fn get_blog_tag_list<T: AsRef<Path>>(
+    base: T,
+    blog_options: &BlogOptions,
+) -> Result<Vec<Tag>, BlogError> {
+    let mut tags: Vec<Tag> = vec![];
 
-        let extension = match path.extension() {
-            Some(x) => x.to_str().unwrap(),
-            None => continue,
-        };
+    for path in blog_options.layout.blog_paths(base.as_ref())? {
+        let (json, _) = get_blog_data(path, blog_options)?;
 
-        if extension != "json" {
+        if !is_published(json.draft, json.publish_at, blog_options) {
             continue;
         }
 
-        let parent = match path.parent() {
-            Some(x) => x,
-            None => return Err(BlogError::FileNotFound),
-        };
-
-        let file_name = match path.file_name() {
-            Some(x) => x,
-            None => return Err(BlogError::FileNotFound),
-        };
+        for (name, slug) in json.tags.into_iter().zip(json.tag_slugs) {
+            if !tags.iter().any(|x| x.slug == slug) {
+                tags.push(Tag {
+                    name: name,
+                    slug: slug,
+                })
+            }
+        }
+    }
 
-        let file_name = match file_name.to_str() {
-            Some(x) => x,
-            None => return Err(BlogError::FileNotFound),
-        };
+    return Ok(tags);
+}
 
-        let md_file_name = file_name.replace(".json", ".md");
+/// Use this function to get the number of blogs with each tag, from most to
+/// least used. Only the metadata is read, no markdown is rendered. Pass the
+/// result to `tag_cloud` to weight the tags for a tag cloud
+///
+/// `blog_options` configures which blogs are included e.g. whether drafts are
+/// shown
+pub fn get_tag_counts<T: AsRef<Path>>(
+    base: T,
+    blog_options: &BlogOptions,
+) -> Result<Vec<TagCount>, BlogError> {
+    let mut counts: Vec<TagCount> = vec![];
 
-        let md_file_path = parent.join(md_file_name);
+    for path in blog_options.layout.blog_paths(base.as_ref())? {
+        let (json, _) = get_blog_data(path, blog_options)?;
 
-        if !md_file_path.exists() {
+        if !is_published(json.draft, json.publish_at, blog_options) {
             continue;
         }
 
-        let json_text = get_json_data(path)?;
+        // The tags of a blog are never repeated once they are normalised
+        for (name, slug) in json.tags.into_iter().zip(json.tag_slugs) {
+            match counts.iter_mut().find(|x| x.slug == slug) {
+                Some(x) => x.count += 1,
+                None => counts.push(TagCount {
+                    name: name,
+                    slug: slug,
+                    count: 1,
+                }),
+            }
+        }
+    }
+
+    return Ok(sorted_counts(counts));
+}
+
+/// Use this function to group the blogs by year and then by month, newest
+/// first, for an archive page. The `slugs` of each month are left empty
+///
+/// The metadata of each blog is read, and `blog_options` decides which blogs
+/// are included e.g. whether drafts are shown
+///
+/// Set `from_paths` to only count the blogs when the layout can tell the date
+/// of a blog from its path, such as the `year/yyyy-mm-dd` folders of the
+/// default `DatedLayout`. This is much faster as no blog is read, but drafts
+/// and scheduled blogs are counted too
+pub fn get_archive<T: AsRef<Path>>(
+    base: T,
+    from_paths: bool,
+    blog_options: &BlogOptions,
+) -> Result<Vec<ArchiveYear>, BlogError> {
+    let base = base.as_ref();
+
+    let mut months = vec![];
 
-        for tag in &json_text.tags {
-            if !tags.contains(tag) {
-                tags.push(tag.clone())
+    for path in blog_options.layout.blog_paths(base)? {
+        if from_paths {
+            if let Some(x) = blog_options.layout.blog_month(base, &path) {
+                months.push(x);
+                continue;
             }
         }
+
+        let (json, _) = get_blog_data(path, blog_options)?;
+
+        if !is_published(json.draft, json.publish_at, blog_options) {
+            continue;
+        }
+
+        months.push((json.date.year(), json.date.month()));
     }
 
-    return Ok(tags);
+    return Ok(count_archive(months));
 }
 
 /// This function will find all of the blogs with the specified tag, so they
 /// can be previewed (e.g. on a tag index page). They won't contain the full HTML
 /// of the blog, only a preview.
 ///
-/// Control the legnth of the preview with `preview_length`. Default is 320 chars
+/// The tag can be its name, its slug or one of its aliases
+///
+/// Control the legnth of the preview with `preview_length`. Default is 320 characters
+///
+/// `blog_options` configures which blogs are included e.g. whether drafts are
+/// shown
 pub fn preview_blogs_tagged<T: AsRef<Path>>(
     base: T,
     tag: String,
     preview_length: Option<usize>,
+    blog_options: &BlogOptions,
 ) -> Result<Vec<PreviewBlogEntry>, BlogError> {
-    let mut blogs = vec![];
-
-    for entry in WalkDir::new(base) {
-        let entry = entry.unwrap();
+    let tag = normalise_tag(&tag, &blog_options.tags);
 
-        let f_n = match entry.file_name().to_str() {
-            Some(x) => x,
-            None => return Err(BlogError::FileNotFound),
-        };
-
-        if !f_n.ends_with(".json") {
-            continue;
-        }
+    let mut blogs = vec![];
 
-        let path = entry.path();
-        let json = get_json_data(path)?;
+    for path in blog_options.layout.blog_paths(base.as_ref())? {
+        let (json, md) = get_blog_data(path, blog_options)?;
 
-        if !json.tags.contains(&tag) {
+        if !json.tag_slugs.contains(&tag.slug)
+            || !is_published(json.draft, json.publish_at, blog_options)
+        {
             continue;
         }
 
         // Great! We've found the blog post!
 
-        let parent = match path.parent() {
-            Some(x) => x,
-            None => return Err(BlogError::FileNotFound),
-        };
-
-        let file_name = match path.file_name() {
-            Some(x) => x,
-            None => return Err(BlogError::FileNotFound),
-        };
-
-        let file_name = match file_name.to_str() {
-            Some(x) => x,
-            None => return Err(BlogError::FileNotFound),
-        };
-
-        let file_base = file_name.replace(".json", ".md");
-
-        let md_path = parent.join(file_base);
-
-        let md = match fs::read_to_string(md_path) {
-            Ok(x) => x,
-            Err(y) => return Err(BlogError::File(y)),
-        };
-
         // TODO: tbh I'm not very happy with rendering the HTML every single time here
-        let html = match to_html_with_options(
-            &md,
-            &Options {
-                compile: markdown::CompileOptions {
-                    allow_dangerous_html: true,
-                    allow_dangerous_protocol: true,
-
-                    ..markdown::CompileOptions::default()
-                },
-                ..markdown::Options::default()
-            },
-        ) {
-            Ok(x) => x,
-            Err(y) => return Err(BlogError::Markdown(y)),
-        };
-
-        let preview = get_preview(&html, preview_length);
-
-        let blog = PreviewBlogEntry::new(json, preview);
+        let blog = preview_entry(json, &md, preview_length, blog_options)?;
 
         blogs.push(blog);
     }
@@ -152,183 +177,547 @@ pub fn preview_blogs_tagged<T: AsRef<Path>>(
 /// Renders an individual blog post.
 ///
 /// Provide the date of the blog post and the slug (the slug as found in the
-/// "slug" field of the JSON).
+/// "slug" field of the metadata).
 ///
 /// Optionally, provide a table of contents generation function
 ///
-/// Returns `None` if the specified blog can not be found
+/// Returns `None` if the specified blog can not be found, or if it is a draft
+/// and `include_drafts` is not set in the `blog_options`
 pub fn render_blog_post<T: AsRef<Path>>(
     base: T,
     date: String,
     slug: String,
     toc_generation_func: Option<&dyn Fn(&Node) -> String>,
+    blog_options: &BlogOptions,
 ) -> Result<Option<LowBlogEntry>, BlogError> {
-    let base = base.as_ref();
-    let split: Vec<&str> = date.split("-").collect();
+    let date = match date.parse::<NaiveDate>() {
+        Ok(x) => x,
+        Err(_) => return Err(BlogError::ImproperDate(date)),
+    };
 
-    let year = match split.get(0) {
-        Some(&x) => x,
-        None => return Err(BlogError::ImproperDate(date.clone())),
+    let (json, md) = match find_blog(base, date, &slug, blog_options)? {
+        Some(x) => x,
+        None => return Ok(None),
     };
 
-    let folder = base.join(format!("{}", year)).join(format!("{}", date));
+    // Great! We've found the blog post!
 
-    for entry in WalkDir::new(folder) {
-        let entry = entry.unwrap();
+    if !is_published(json.draft, json.publish_at, blog_options) {
+        return Ok(None);
+    }
 
-        let f_n = match entry.file_name().to_str() {
-            Some(x) => x,
-            None => return Err(BlogError::FileNotFound),
-        };
+    let html = render_markdown(&md, json.safety, blog_options)?;
 
-        if !f_n.ends_with(".json") {
-            continue;
-        }
+    let toc = toc(&md, toc_generation_func, &blog_options.render)?;
 
-        let path = entry.path();
-        let json = get_json_data(path)?;
+    let preview = get_preview(&html, &md, None, json.safety, blog_options)?;
 
-        if json.slug != slug {
-            continue;
-        }
+    let excerpt = get_excerpt(&html, &md, json.safety, blog_options)?;
 
-        // Great! We've found the blog post!
+    let word_count = count_words(&md, &blog_options.render)?;
 
-        let parent = match path.parent() {
-            Some(x) => x,
-            None => return Err(BlogError::FileNotFound),
-        };
+    let reading_time = reading_time(word_count, blog_options);
 
-        // TODO: I've done this little two stage file name thing several times - there must be scope for abstracting this into a function
-        let file_name = match path.file_name() {
-            Some(x) => x,
-            None => return Err(BlogError::FileNotFound),
-        };
+    return Ok(Some(LowBlogEntry::new(
+        json,
+        html,
+        toc,
+        preview,
+        excerpt,
+        word_count,
+        reading_time,
+    )));
+}
 
-        let file_name = match file_name.to_str() {
-            Some(x) => x,
-            None => return Err(BlogError::FileNotFound),
-        };
+/// Previews blogs for an index page. Will order from newest to oldest
+///
+/// `num` controls how many blogs will be in the preview
+///
+/// `preview_length` is how long each preview will be. Default is 320 characters
+///
+/// `blog_options` configures which blogs are included e.g. whether drafts are
+/// shown
+pub fn preview_blogs<T: AsRef<Path>>(
+    base: T,
+    num: usize,
+    preview_length: Option<usize>,
+    blog_options: &BlogOptions,
+) -> Result<Vec<PreviewBlogEntry>, BlogError> {
+    // Only the metadata is needed to find the newest blogs, so nothing is
+    // rendered until we know which blogs are in the preview
+    let found = find_published(base.as_ref(), None, blog_options)?;
 
-        let file_base = file_name.replace(".json", ".md");
+    let mut blogs = vec![];
 
-        let md_path = parent.join(file_base);
+    for (json, markdown) in found.into_iter().take(num) {
+        blogs.push(preview_entry(
+            json,
+            &markdown,
+            preview_length,
+            blog_options,
+        )?);
+    }
 
-        let md = match fs::read_to_string(md_path) {
-            Ok(x) => x,
-            Err(y) => return Err(BlogError::File(y)),
-        };
+    blogs.sort_by_key(|x| Reverse(x.date));
 
-        let html = match to_html_with_options(
-            &md,
-            &Options {
-                compile: markdown::CompileOptions {
-                    allow_dangerous_html: true,
-                    allow_dangerous_protocol: true,
-
-                    ..markdown::CompileOptions::default()
-                },
-                ..markdown::Options::default()
-            },
-        ) {
-            Ok(x) => x,
-            Err(y) => return Err(BlogError::Markdown(y)),
-        };
+    return Ok(blogs);
+}
 
-        let toc = toc(&md, toc_generation_func)?;
+/// Previews page `page` of the blogs for an index page, newest first. Pages
+/// start from 1, and `None` is returned if there is no such page. Only the
+/// blogs on the page are rendered
+///
+/// `preview_length` is how long each preview will be. Default is 320 characters
+///
+/// The URLs of the pages are made from the `blog_root_slug` of the
+/// `sitemap_options`
+///
+/// `blog_options` configures which blogs are included e.g. whether drafts are
+/// shown
+pub fn preview_blogs_page<T: AsRef<Path>>(
+    base: T,
+    page: usize,
+    preview_length: Option<usize>,
+    pagination_options: &PaginationOptions,
+    sitemap_options: &SitemapOptions,
+    blog_options: &BlogOptions,
+) -> Result<Option<Page<PreviewBlogEntry>>, BlogError> {
+    let found = find_published(base.as_ref(), None, blog_options)?;
+
+    return preview_page(
+        found,
+        page,
+        preview_length,
+        &sitemap_options.blog_root_slug,
+        pagination_options,
+        blog_options,
+    );
+}
+
+/// Previews page `page` of the blogs with the tag `tag` for a tag page, newest
+/// first. The tag can be its name, its slug or one of its aliases. Pages start
+/// from 1, and `None` is returned if there is no such page or no blog has the
+/// tag. Only the blogs on the page are rendered
+///
+/// `preview_length` is how long each preview will be. Default is 320 characters
+///
+/// The URLs of the pages are made from the `tag_root_slug` of the
+/// `sitemap_options`
+///
+/// `blog_options` configures which blogs are included e.g. whether drafts are
+/// shown
+pub fn preview_blogs_tagged_page<T: AsRef<Path>>(
+    base: T,
+    tag: String,
+    page: usize,
+    preview_length: Option<usize>,
+    pagination_options: &PaginationOptions,
+    sitemap_options: &SitemapOptions,
+    blog_options: &BlogOptions,
+) -> Result<Option<Page<PreviewBlogEntry>>, BlogError> {
+    let tag = normalise_tag(&tag, &blog_options.tags);
+
+    let found = find_published(base.as_ref(), Some(&tag.slug), blog_options)?;
 
-        return Ok(Some(LowBlogEntry::new(json, html, toc)));
+    if found.is_empty() {
+        return Ok(None);
     }
 
-    return Ok(None);
+    let root_slug = format!(
+        "{}/{}",
+        sitemap_options.tag_root_slug,
+        encode_slug(&tag.slug)
+    );
+
+    return preview_page(
+        found,
+        page,
+        preview_length,
+        &root_slug,
+        pagination_options,
+        blog_options,
+    );
 }
 
-/// Previews blogs for an index page. Will order from newest to oldest
+/// Use this function to export a search index of every blog, for searching in
+/// the browser. See `SearchExport` for the format
 ///
-/// `num` controls how many blogs will be in the preview
+/// Every blog is read and rendered, so this is best done once when the blog is
+/// deployed rather than on every request
 ///
-/// `preview_length` is how long each preview will be. Default is 320 chars
-pub fn preview_blogs<T: AsRef<Path>>(
+/// Optionally specify the number of chars for the preview. Default is 320.
+///
+/// The URLs of the blogs are made from the `blog_root_slug` of the
+/// `sitemap_options`
+///
+/// `blog_options` configures which blogs are included e.g. whether drafts are
+/// shown
+pub fn export_search<T: AsRef<Path>>(
     base: T,
-    num: usize,
     preview_length: Option<usize>,
+    export_options: &SearchExportOptions,
+    sitemap_options: &SitemapOptions,
+    blog_options: &BlogOptions,
+) -> Result<SearchExport, BlogError> {
+    let found = find_published(base.as_ref(), None, blog_options)?;
+
+    let mut entries = vec![];
+
+    for (json, markdown) in found {
+        let html = render_markdown(&markdown, json.safety, blog_options)?;
+
+        let preview = get_preview(&html, &markdown, preview_length, json.safety, blog_options)?;
+
+        let word_count = count_words(&markdown, &blog_options.render)?;
+
+        let reading_time = reading_time(word_count, blog_options);
+
+        entries.push(LowBlogEntry::new(
+            json,
+            html,
+            None,
+            preview,
+            None,
+            word_count,
+            reading_time,
+        ));
+    }
+
+    return export_documents(&entries, export_options, sitemap_options);
+}
+
+/// Use this function to get the related blogs of the blog with this date and
+/// slug, best first, for a "you might also like" section. There are none if
+/// there is no such blog
+///
+/// Every blog is read and rendered to compare its text with the blog, so with
+/// many blogs this function could become slow - consider caching the result
+///
+/// Optionally specify the number of chars for the preview. Default is 320.
+///
+/// `blog_options` configures which blogs are included e.g. whether drafts are
+/// shown
+pub fn get_related_blogs<T: AsRef<Path>>(
+    base: T,
+    date: String,
+    slug: String,
+    preview_length: Option<usize>,
+    related_options: &RelatedOptions,
+    blog_options: &BlogOptions,
 ) -> Result<Vec<PreviewBlogEntry>, BlogError> {
-    let mut json_paths = vec![];
+    let date = match date.parse::<NaiveDate>() {
+        Ok(x) => x,
+        Err(_) => return Err(BlogError::ImproperDate(date)),
+    };
+
+    let found = find_published(base.as_ref(), None, blog_options)?;
+
+    let mut index = None;
+    let mut profiles = vec![];
+
+    for (i, (json, markdown)) in found.iter().enumerate() {
+        if json.date == date && json.slug == slug {
+            index = Some(i);
+        }
+
+        let html = render_markdown(markdown, json.safety, blog_options)?;
+
+        profiles.push(profile(
+            json.tag_slugs.clone(),
+            json.keywords.clone(),
+            &html_text(&html),
+        ));
+    }
+
+    let index = match index {
+        Some(x) => x,
+        None => return Ok(vec![]),
+    };
 
-    for entry in WalkDir::new(base) {
-        let entry = entry.unwrap();
+    let mut found: Vec<Option<(BlogJson, String)>> = found.into_iter().map(Some).collect();
 
-        let path = entry.path();
+    let mut blogs = vec![];
 
-        let extension = match path.extension() {
+    for i in top_related(&profiles, index, related_options) {
+        let (json, markdown) = match found[i].take() {
             Some(x) => x,
             None => continue,
         };
 
-        if extension == "json" {
-            json_paths.push(path.to_path_buf());
+        blogs.push(preview_entry(
+            json,
+            &markdown,
+            preview_length,
+            blog_options,
+        )?);
+    }
+
+    return Ok(blogs);
+}
+
+/// Use this function to get the blogs either side of the blog with this date
+/// and slug, for previous and next links. With a `tag`, only the blogs with
+/// that tag are neighbours. `None` if there is no such blog, or it does not
+/// have the tag
+///
+/// With the default `DatedLayout` only the blogs in the date folders nearest
+/// to the blog are read. Other layouts read every blog
+///
+/// Optionally specify the number of chars for the preview. Default is 320.
+///
+/// `blog_options` configures which blogs are included e.g. whether drafts are
+/// shown
+pub fn get_neighbour_blogs<T: AsRef<Path>>(
+    base: T,
+    date: String,
+    slug: String,
+    tag: Option<String>,
+    preview_length: Option<usize>,
+    blog_options: &BlogOptions,
+) -> Result<Option<Neighbours<PreviewBlogEntry>>, BlogError> {
+    let date = match date.parse::<NaiveDate>() {
+        Ok(x) => x,
+        Err(_) => return Err(BlogError::ImproperDate(date)),
+    };
+
+    let base = base.as_ref();
+
+    let tag_slug = tag.map(|x| normalise_tag(&x, &blog_options.tags).slug);
+
+    let folders = match blog_options.layout.date_folders(base)? {
+        Some(x) => x,
+        None => {
+            let found = find_published(base, tag_slug.as_ref(), blog_options)?;
+
+            let index = match found
+                .iter()
+                .position(|x| x.0.date == date && x.0.slug == slug)
+            {
+                Some(x) => x,
+                None => return Ok(None),
+            };
+
+            let mut found: Vec<Option<(BlogJson, String)>> = found.into_iter().map(Some).collect();
+
+            let prev = take_found(&mut found, index + 1);
+            let next = match index {
+                0 => None,
+                x => take_found(&mut found, x - 1),
+            };
+
+            return neighbour_previews(prev, next, preview_length, blog_options);
+        }
+    };
+
+    let current = match folders.binary_search_by_key(&date, |x| x.0) {
+        Ok(x) => x,
+        Err(_) => return Ok(None),
+    };
+
+    let here = find_published_in(
+        get_blog_paths(&folders[current].1, None)?,
+        tag_slug.as_ref(),
+        blog_options,
+    )?;
+
+    let index = match here
+        .iter()
+        .position(|x| x.0.date == date && x.0.slug == slug)
+    {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+
+    let mut here: Vec<Option<(BlogJson, String)>> = here.into_iter().map(Some).collect();
+
+    // Older blogs are after this one in its own folder, then the newest blog in
+    // the nearest older folder with one
+    let mut prev = take_found(&mut here, index + 1);
+
+    for (_, folder) in folders[..current].iter().rev() {
+        if prev.is_some() {
+            break;
         }
+
+        let found = find_published_in(
+            get_blog_paths(folder, None)?,
+            tag_slug.as_ref(),
+            blog_options,
+        )?;
+
+        prev = found.into_iter().next();
     }
 
-    let mut blogs = vec![];
+    // Newer blogs are before this one in its own folder, then the oldest blog in
+    // the nearest newer folder with one
+    let mut next = match index {
+        0 => None,
+        x => take_found(&mut here, x - 1),
+    };
 
-    for i in 0..num {
-        let this_path = match json_paths.get(i) {
-            Some(x) => x,
-            None => break,
-        };
+    for (_, folder) in &folders[current + 1..] {
+        if next.is_some() {
+            break;
+        }
 
-        let json = get_json_data(this_path)?;
+        let found = find_published_in(
+            get_blog_paths(folder, None)?,
+            tag_slug.as_ref(),
+            blog_options,
+        )?;
 
-        let parent = match json_paths[i].parent() {
-            // We can use [i] here since we know this must exist :)
-            Some(x) => x,
-            None => return Err(BlogError::FileNotFound),
-        };
+        next = found.into_iter().last();
+    }
 
-        let file_name = match json_paths[i].file_name() {
-            Some(x) => x,
-            None => return Err(BlogError::FileNotFound),
-        };
+    return neighbour_previews(prev, next, preview_length, blog_options);
+}
 
-        let file_name = match file_name.to_str() {
-            Some(x) => x,
-            None => return Err(BlogError::FileNotFound),
-        };
+// The metadata and markdown of every published blog, newest first. If there is
+// a `tag_slug` then only the blogs with that tag are kept
+fn find_published(
+    base: &Path,
+    tag_slug: Option<&String>,
+    blog_options: &BlogOptions,
+) -> Result<Vec<(BlogJson, String)>, BlogError> {
+    let paths = blog_options.layout.blog_paths(base)?;
 
-        let file_name = file_name.replace(".json", ".md");
+    return find_published_in(paths, tag_slug, blog_options);
+}
 
-        let md_path = parent.join(file_name);
+// The published blogs among `paths`, newest first
+fn find_published_in(
+    paths: Vec<PathBuf>,
+    tag_slug: Option<&String>,
+    blog_options: &BlogOptions,
+) -> Result<Vec<(BlogJson, String)>, BlogError> {
+    let mut found = vec![];
 
-        let markdown = match fs::read_to_string(md_path) {
-            Ok(x) => x,
-            Err(y) => return Err(BlogError::File(y)),
-        };
+    for path in paths {
+        let (json, markdown) = get_blog_data(path, blog_options)?;
 
-        let html = match to_html_with_options(
-            &markdown,
-            &Options {
-                compile: markdown::CompileOptions {
-                    allow_dangerous_html: true,
-                    allow_dangerous_protocol: true,
-
-                    ..markdown::CompileOptions::default()
-                },
-                ..markdown::Options::default()
-            },
-        ) {
-            Ok(x) => x,
-            Err(y) => return Err(BlogError::Markdown(y)),
-        };
+        if !is_published(json.draft, json.publish_at, blog_options) {
+            continue;
+        }
+
+        if let Some(x) = tag_slug {
+            if !json.tag_slugs.contains(x) {
+                continue;
+            }
+        }
+
+        found.push((json, markdown));
+    }
 
-        let preview: String = get_preview(&html, preview_length);
+    found.sort_by_key(|x| Reverse(x.0.date));
 
-        let blog_preview = PreviewBlogEntry::new(json, preview);
+    return Ok(found);
+}
 
-        blogs.push(blog_preview);
+// Renders the previews of the blogs on page `page`, skipping over the blogs on
+// the pages before it
+fn preview_page(
+    found: Vec<(BlogJson, String)>,
+    page: usize,
+    preview_length: Option<usize>,
+    root_slug: &str,
+    pagination_options: &PaginationOptions,
+    blog_options: &BlogOptions,
+) -> Result<Option<Page<PreviewBlogEntry>>, BlogError> {
+    let total_items = found.len();
+
+    if page == 0 || page > total_pages(total_items, pagination_options) {
+        return Ok(None);
     }
 
-    blogs.sort_by(|a, b| b.date.cmp(&a.date));
+    let page_size = pagination_options.page_size.max(1);
 
-    return Ok(blogs);
+    let mut blogs = vec![];
+
+    for (json, markdown) in found
+        .into_iter()
+        .skip((page - 1) * page_size)
+        .take(page_size)
+    {
+        blogs.push(preview_entry(
+            json,
+            &markdown,
+            preview_length,
+            blog_options,
+        )?);
+    }
+
+    return Ok(Some(new_page(
+        blogs,
+        page,
+        total_items,
+        root_slug,
+        pagination_options,
+    )));
+}
+
+fn take_found(
+    found: &mut [Option<(BlogJson, String)>],
+    index: usize,
+) -> Option<(BlogJson, String)> {
+    return match found.get_mut(index) {
+        Some(x) => x.take(),
+        None => None,
+    };
+}
+
+fn neighbour_previews(
+    prev: Option<(BlogJson, String)>,
+    next: Option<(BlogJson, String)>,
+    preview_length: Option<usize>,
+    blog_options: &BlogOptions,
+) -> Result<Option<Neighbours<PreviewBlogEntry>>, BlogError> {
+    let prev = match prev {
+        Some((json, markdown)) => Some(preview_entry(
+            json,
+            &markdown,
+            preview_length,
+            blog_options,
+        )?),
+        None => None,
+    };
+
+    let next = match next {
+        Some((json, markdown)) => Some(preview_entry(
+            json,
+            &markdown,
+            preview_length,
+            blog_options,
+        )?),
+        None => None,
+    };
+
+    return Ok(Some(Neighbours {
+        prev: prev,
+        next: next,
+    }));
+}
+
+fn preview_entry(
+    json: BlogJson,
+    markdown: &str,
+    preview_length: Option<usize>,
+    blog_options: &BlogOptions,
+) -> Result<PreviewBlogEntry, BlogError> {
+    let html = render_markdown(markdown, json.safety, blog_options)?;
+
+    let preview = get_preview(&html, markdown, preview_length, json.safety, blog_options)?;
+
+    let excerpt = get_excerpt(&html, markdown, json.safety, blog_options)?;
+
+    let word_count = count_words(markdown, &blog_options.render)?;
+
+    let reading_time = reading_time(word_count, blog_options);
+
+    return Ok(PreviewBlogEntry::new(
+        json,
+        preview,
+        excerpt,
+        word_count,
+        reading_time,
+    ));
 }
@@ -1,12 +1,11 @@
-use std::fs;
-
-use chrono::NaiveDate;
-use markdown::{to_html_with_options, Options};
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 use crate::{
-    common::{get_json_data, preview::get_preview, toc, BlogError, BlogJson},
-    types::Blog,
+    common::{count_words, get_blog_data, reading_time, render_markdown, toc, BlogJson},
+    preview::{get_excerpt, get_preview},
+    types::{Blog, BlogOptions},
 };
 
 /// An individual blog post
@@ -24,6 +23,8 @@ pub struct LowBlogEntry {
     pub slug: String,
     /// `Vec` of tags for this blog
     pub tags: Vec<String>,
+    /// URL slugs of the tags, in the same order as `tags`
+    pub tag_slugs: Vec<String>,
     /// Table of contents
     pub toc: Option<String>,
     /// Optional `Vec` of keywords. Intended for SEO in comparison to tags
@@ -36,7 +37,16 @@ pub struct LowBlogEntry {
     pub author_webpage: Option<String>,
     last_modified: Option<NaiveDate>,
     priority: Option<f64>,
-    previw_chars: Option<usize>,
+    preview: String,
+    excerpt: Option<String>,
+    draft: bool,
+    publish_at: Option<NaiveDateTime>,
+    /// Number of words, not counting code blocks
+    pub word_count: usize,
+    /// Estimated reading time in minutes
+    pub reading_time: usize,
+    /// Any metadata which `blog-tools` does not use itself
+    pub extra: Map<String, Value>,
 }
 
 impl Blog for LowBlogEntry {
@@ -44,55 +54,38 @@ impl Blog for LowBlogEntry {
         blog: T,
         toc_generation_func: Option<&dyn Fn(&markdown::mdast::Node) -> String>,
         preview_chars: Option<usize>,
+        blog_options: &BlogOptions,
     ) -> Result<Self, crate::common::BlogError> {
-        let json = get_json_data(&blog)?;
+        let (json, markdown) = get_blog_data(&blog, blog_options)?;
 
-        let markdown = match fs::read_to_string(blog) {
-            Ok(x) => x,
-            Err(y) => return Err(BlogError::File(y)),
-        };
+        let html = render_markdown(&markdown, json.safety, blog_options)?;
 
-        let html = match to_html_with_options(
-            &markdown,
-            &Options {
-                compile: markdown::CompileOptions {
-                    allow_dangerous_html: true,
-                    allow_dangerous_protocol: true,
-
-                    ..markdown::CompileOptions::default()
-                },
-                ..markdown::Options::default()
-            },
-        ) {
-            Ok(x) => x,
-            Err(y) => return Err(BlogError::Markdown(y)),
-        };
+        let toc = toc(&markdown, toc_generation_func, &blog_options.render)?;
 
-        let toc = toc(&markdown, toc_generation_func)?;
+        let preview = get_preview(&html, &markdown, preview_chars, json.safety, blog_options)?;
 
-        return Ok(LowBlogEntry {
-            title: json.title,
-            date: json.date,
-            desc: json.desc,
-            html: html,
-            slug: json.slug,
-            tags: json.tags,
-            toc: toc,
-            keywords: json.keywords,
-            canonical_link: json.canonical_link,
-            author_name: json.author_name,
-            author_webpage: json.author_webpage,
-            last_modified: json.last_modified,
-            priority: json.priority,
-            previw_chars: preview_chars,
-        });
+        let excerpt = get_excerpt(&html, &markdown, json.safety, blog_options)?;
+
+        let word_count = count_words(&markdown, &blog_options.render)?;
+
+        let reading_time = reading_time(word_count, blog_options);
+
+        return Ok(LowBlogEntry::new(
+            json,
+            html,
+            toc,
+            preview,
+            excerpt,
+            word_count,
+            reading_time,
+        ));
     }
     fn get_title(&self) -> String {
         return self.title.clone();
     }
 
     fn get_date_listed(&self) -> NaiveDate {
-        return self.date.clone();
+        return self.date;
     }
 
     fn get_description(&self) -> Option<String> {
@@ -115,6 +108,10 @@ impl Blog for LowBlogEntry {
         return self.tags.clone();
     }
 
+    fn get_tag_slugs(&self) -> Vec<String> {
+        return self.tag_slugs.clone();
+    }
+
     fn get_table_of_contents(&self) -> Option<String> {
         return self.toc.clone();
     }
@@ -136,22 +133,52 @@ impl Blog for LowBlogEntry {
     }
 
     fn get_preview(&self) -> String {
-        let preview = get_preview(&self.html, self.previw_chars);
+        return self.preview.clone();
+    }
+
+    fn get_excerpt(&self) -> Option<String> {
+        return self.excerpt.clone();
+    }
+
+    fn get_word_count(&self) -> usize {
+        return self.word_count;
+    }
 
-        return preview;
+    fn get_reading_time(&self) -> usize {
+        return self.reading_time;
     }
 
     fn get_last_modified(&self) -> Option<NaiveDate> {
-        return self.last_modified.clone();
+        return self.last_modified;
     }
 
     fn get_priority(&self) -> Option<f64> {
-        return self.priority.clone();
+        return self.priority;
+    }
+
+    fn is_draft(&self) -> bool {
+        return self.draft;
+    }
+
+    fn get_publish_at(&self) -> Option<NaiveDateTime> {
+        return self.publish_at;
+    }
+
+    fn get_extra(&self) -> Map<String, Value> {
+        return self.extra.clone();
     }
 }
 
 impl LowBlogEntry {
-    pub(crate) fn new(json: BlogJson, html: String, toc: Option<String>) -> Self {
+    pub(crate) fn new(
+        json: BlogJson,
+        html: String,
+        toc: Option<String>,
+        preview: String,
+        excerpt: Option<String>,
+        word_count: usize,
+        reading_time: usize,
+    ) -> Self {
         return LowBlogEntry {
             title: json.title,
             date: json.date,
@@ -159,6 +186,7 @@ impl LowBlogEntry {
             html: html,
             slug: json.slug,
             tags: json.tags,
+            tag_slugs: json.tag_slugs,
             toc: toc,
             keywords: json.keywords,
             canonical_link: json.canonical_link,
@@ -166,7 +194,13 @@ impl LowBlogEntry {
             author_webpage: json.author_webpage,
             last_modified: json.last_modified,
             priority: json.priority,
-            previw_chars: None,
+            preview: preview,
+            excerpt: excerpt,
+            draft: json.draft,
+            publish_at: json.publish_at,
+            word_count: word_count,
+            reading_time: reading_time,
+            extra: json.extra,
         };
     }
 }
@@ -184,6 +218,8 @@ pub struct PreviewBlogEntry {
     pub slug: String,
     /// `Vec` of tags for this blog
     pub tags: Vec<String>,
+    /// URL slugs of the tags, in the same order as `tags`
+    pub tag_slugs: Vec<String>,
     /// Optional `Vec` of keywords. Intended for SEO in comparison to tags
     pub keywords: Option<Vec<String>>,
     /// Optional canonical link, intended for SEO
@@ -194,21 +230,47 @@ pub struct PreviewBlogEntry {
     pub author_webpage: Option<String>,
     /// Preview of the blogpost, useful for showing on index pages
     pub preview: String,
+    /// HTML excerpt of the blogpost, if `excerpt_blocks` is set in the
+    /// `PreviewOptions`
+    pub excerpt: Option<String>,
+    /// Whether the blog is a draft
+    pub draft: bool,
+    /// Optional time the blog is scheduled to be published, in UTC
+    pub publish_at: Option<NaiveDateTime>,
+    /// Number of words, not counting code blocks
+    pub word_count: usize,
+    /// Estimated reading time in minutes
+    pub reading_time: usize,
+    /// Any metadata which `blog-tools` does not use itself
+    pub extra: Map<String, Value>,
 }
 
 impl PreviewBlogEntry {
-    pub(crate) fn new(json: BlogJson, preview: String) -> Self {
+    pub(crate) fn new(
+        json: BlogJson,
+        preview: String,
+        excerpt: Option<String>,
+        word_count: usize,
+        reading_time: usize,
+    ) -> Self {
         return PreviewBlogEntry {
             title: json.title,
             date: json.date,
             desc: json.desc,
             slug: json.slug,
             tags: json.tags,
+            tag_slugs: json.tag_slugs,
             keywords: json.keywords,
             canonical_link: json.canonical_link,
             author_name: json.author_name,
             author_webpage: json.author_webpage,
             preview: preview,
+            excerpt: excerpt,
+            draft: json.draft,
+            publish_at: json.publish_at,
+            word_count: word_count,
+            reading_time: reading_time,
+            extra: json.extra,
         };
     }
 }
@@ -1,4 +1,5 @@
 use std::{
+    cmp::Reverse,
     collections::HashMap,
     path::{Path, PathBuf},
 };
@@ -6,9 +7,16 @@ use std::{
 use markdown::mdast::Node;
 
 use crate::{
+    archive::build_archive,
     common::{parse_blogs, BlogError},
+    feed::{create_feeds_inner, FeedOptions},
+    high::HighBlogEntry,
+    navigation::positions,
+    related::related_index,
+    search::build_index,
     sitemap::{create_sitemap_inner, SitemapOptions},
-    Blog,
+    tags::{tag_index, Tag},
+    Blog, BlogOptions,
 };
 
 use super::types::{MediumBlog, MediumBlogEntry};
@@ -16,12 +24,18 @@ use super::types::{MediumBlog, MediumBlogEntry};
 /// Gets the whole `MediumBlog` from the specified path. Useful to combine with lazy
 /// static for loading times
 ///
-/// The path should be a folder which contains markdown files next to json files
+/// The path should be a folder which contains markdown files next to json files,
+/// or markdown files with front matter
 ///
 /// Add an optional toc generation function if you want a table of contents
 ///
 /// Optionally specify the number of chars for the preview. Default is 320.
 ///
+/// `sitemap_options` and `feed_options` configure the sitemap and the feeds
+///
+/// `blog_options` configures which blogs are included e.g. whether drafts are
+/// shown
+///
 /// ```rust,ignore
 /// lazy_static! {
 ///     pub static ref STATIC_BLOG_ENTRIES: MediumBlog =
@@ -36,7 +50,7 @@ use super::types::{MediumBlog, MediumBlogEntry};
 /// context.insert(
 ///     "blog",
 ///     &this_blog
-///         .render(PathBuf::from_str(BLOG_ROOT).unwrap())
+///         .render(PathBuf::from_str(BLOG_ROOT).unwrap(), &BlogOptions::default())
 ///         .unwrap(),
 /// );
 /// ```
@@ -47,6 +61,8 @@ pub fn get_medium_blog(
     preview_chars: Option<usize>,
     url: &String,
     sitemap_options: &SitemapOptions,
+    feed_options: &FeedOptions,
+    blog_options: &BlogOptions,
 ) -> Result<MediumBlog, BlogError> {
     return get_blog_entries(
         base,
@@ -54,6 +70,8 @@ pub fn get_medium_blog(
         preview_chars,
         url,
         sitemap_options,
+        feed_options,
+        blog_options,
     );
 }
 
@@ -63,24 +81,88 @@ pub fn get_blog_entries<T: AsRef<Path>>(
     preview_chars: Option<usize>,
     url: &String,
     sitemap_options: &SitemapOptions,
+    feed_options: &FeedOptions,
+    blog_options: &BlogOptions,
 ) -> Result<MediumBlog, BlogError> {
+    let base = base.as_ref();
     let mut hashes: HashMap<String, MediumBlogEntry> = HashMap::new();
 
-    let (mut entries, tags): (Vec<MediumBlogEntry>, Vec<String>) =
-        parse_blogs(base, toc_generation_func, preview_chars)?;
+    let (mut entries, tags): (Vec<MediumBlogEntry>, Vec<Tag>) =
+        parse_blogs(base, toc_generation_func, preview_chars, blog_options)?;
+
+    entries.sort_by_key(|x| Reverse(x.get_date_listed()));
+
+    // The text is only kept until the search index is built and the related
+    // blogs are found
+    let texts: Vec<String> = entries.iter_mut().map(|x| x.take_text()).collect();
 
     for entry in &entries {
         hashes.insert(entry.get_full_slug(), entry.clone());
     }
 
-    entries.sort_by(|a, b| b.get_date_listed().cmp(&a.get_date_listed()));
+    let tag_index = tag_index(&entries);
+
+    let positions = positions(&entries);
+
+    let archive = build_archive(&entries);
+
+    let search_index = blog_options
+        .search
+        .as_ref()
+        .map(|x| build_index(&entries, &texts, x));
+
+    let related = match &blog_options.related {
+        Some(x) => related_index(&entries, &texts, x),
+        None => HashMap::new(),
+    };
 
     let sitemap = create_sitemap_inner(&entries, Some(&tags), url, sitemap_options)?;
 
+    let feeds = if feed_options.full_html {
+        let rendered = render_feed_entries(&entries, base, feed_options, blog_options)?;
+        create_feeds_inner(&rendered, &tags, url, sitemap_options, feed_options)?
+    } else {
+        create_feeds_inner(&entries, &tags, url, sitemap_options, feed_options)?
+    };
+
     return Ok(MediumBlog {
         hash: hashes,
         entries: entries,
         tags: tags,
+        tag_index: tag_index,
+        positions: positions,
+        archive: archive,
+        search_index: search_index,
+        related: related,
         sitemap: sitemap,
+        feed: feeds.rss,
+        atom_feed: feeds.atom,
+        json_feed: feeds.json,
+        tag_feeds: feeds.tag_rss,
+        tag_atom_feeds: feeds.tag_atom,
+        tag_json_feeds: feeds.tag_json,
     });
 }
+
+// A `MediumBlogEntry` does not hold any HTML, so when a feed needs the full
+// HTML the newest blogs are rendered once while the feed is generated. Tag
+// feeds can contain any blog, so then every blog has to be rendered
+fn render_feed_entries(
+    entries: &[MediumBlogEntry],
+    base: &Path,
+    feed_options: &FeedOptions,
+    blog_options: &BlogOptions,
+) -> Result<Vec<HighBlogEntry>, BlogError> {
+    let limit = match feed_options.limit {
+        Some(x) if !feed_options.include_tag_feeds => x,
+        _ => entries.len(),
+    };
+
+    let mut rendered = vec![];
+
+    for entry in entries.iter().take(limit) {
+        rendered.push(entry.render(base.to_path_buf(), blog_options)?);
+    }
+
+    return Ok(rendered);
+}
@@ -1,13 +1,27 @@
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::{collections::HashMap, path::PathBuf};
 
-use chrono::{Datelike, NaiveDate};
-use markdown::{to_html_with_options, Options};
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 use crate::{
-    common::{get_json_data, preview::get_preview, toc, BlogError, BlogJson},
+    archive::ArchiveYear,
+    common::{
+        count_words, find_blog, get_blog_data, reading_time, render_markdown, toc, BlogError,
+        BlogJson,
+    },
     high::HighBlogEntry,
-    types::Blog,
+    navigation::{neighbours, Neighbours},
+    pagination::{paginate, Page, PaginationOptions},
+    preview::{get_excerpt, get_preview},
+    query::Query,
+    search::{
+        export_documents, html_text, SearchExport, SearchExportOptions, SearchIndex, SearchOptions,
+        SearchResult,
+    },
+    sitemap::SitemapOptions,
+    tags::{encode_slug, sorted_counts, tag_cloud, Tag, TagCloudOptions, TagCount, TagWeight},
+    types::{Blog, BlogOptions},
 };
 
 /// The main `MediumBlog` which stores all relevant information for the blog
@@ -19,7 +33,23 @@ use crate::{
 /// Note that `entries` and `hash` contain the same information
 /// but in different formats for performance reasons
 ///
-/// `tags` is an unsorted `Vec` of all unique tags used in the blog
+/// `tags` is an unsorted `Vec` of all unique tags used in the blog, each with
+/// its name and slug
+///
+/// `tag_index` contains a map from the slug of each tag to the url slugs of
+/// the blogs with it, sorted by date (newest first)
+///
+/// `positions` contains a map from the url slug of each blog to its position
+/// in `entries`
+///
+/// `archive` contains the url slugs of the blogs grouped by year and then by
+/// month, newest first
+///
+/// `search_index` contains the full text search index, if `search` is set in
+/// the `BlogOptions`
+///
+/// `related` contains a map from the url slug of each blog to the url slugs
+/// of its related blogs, if `related` is set in the `BlogOptions`
 ///
 #[derive(Serialize, Deserialize)]
 pub struct MediumBlog {
@@ -34,9 +64,202 @@ pub struct MediumBlog {
     /// `Vec` of all unique tags
     ///
     /// Useful when you want to list all tags e.g. on an index page
-    pub tags: Vec<String>,
+    pub tags: Vec<Tag>,
+    /// Tag slug to the URL slugs of every blog with that tag, newest first
+    ///
+    /// Useful when you have a GET request to /blog/tag/\<tag\>, along with
+    /// `hash`
+    pub tag_index: HashMap<String, Vec<String>>,
+    /// URL slug to the position of the blog in `entries`
+    ///
+    /// Useful for previous and next links, along with `get_neighbours`
+    pub positions: HashMap<String, usize>,
+    /// The blogs grouped by year and then by month, newest first
+    ///
+    /// Useful when you want to list all blog posts on an archive page
+    pub archive: Vec<ArchiveYear>,
+    /// Full text search index of every blog. `None` unless `search` is set in
+    /// the `BlogOptions`
+    ///
+    /// Useful when you have a GET request to /blog/search?q=\<query\>, along
+    /// with `search`
+    pub search_index: Option<SearchIndex>,
+    /// URL slug to the URL slugs of the related blogs, best first. Empty
+    /// unless `related` is set in the `BlogOptions`
+    ///
+    /// Useful for a "you might also like" section, along with `get_related`
+    pub related: HashMap<String, Vec<String>>,
     /// `String` representation of the sitemap
     pub sitemap: String,
+    /// XML representation of the RSS feed
+    pub feed: String,
+    /// XML representation of the Atom feed
+    pub atom_feed: String,
+    /// JSON representation of the JSON Feed
+    pub json_feed: String,
+    /// Tag slug to the XML representation of the RSS feed for that tag. Empty
+    /// unless `include_tag_feeds` is set in the `FeedOptions`
+    ///
+    /// Useful when you have a GET request to /blog/tag/\<tag\>/feed.xml
+    pub tag_feeds: HashMap<String, String>,
+    /// Tag slug to the XML representation of the Atom feed for that tag. Empty
+    /// unless `include_tag_feeds` is set in the `FeedOptions`
+    pub tag_atom_feeds: HashMap<String, String>,
+    /// Tag slug to the JSON representation of the JSON Feed for that tag. Empty
+    /// unless `include_tag_feeds` is set in the `FeedOptions`
+    pub tag_json_feeds: HashMap<String, String>,
+}
+
+impl MediumBlog {
+    /// Starts a query over `entries`, to filter and sort them. See `Query`
+    pub fn query(&self) -> Query<'_, MediumBlogEntry> {
+        return Query::new(&self.entries);
+    }
+
+    /// Gets page `page` of `entries`, for an index page. Pages start from 1,
+    /// and `None` is returned if there is no such page
+    ///
+    /// The URLs of the pages are made from the `blog_root_slug` of the
+    /// `sitemap_options`
+    pub fn get_page(
+        &self,
+        page: usize,
+        pagination_options: &PaginationOptions,
+        sitemap_options: &SitemapOptions,
+    ) -> Option<Page<MediumBlogEntry>> {
+        return paginate(
+            &self.entries,
+            self.entries.len(),
+            page,
+            &sitemap_options.blog_root_slug,
+            pagination_options,
+        );
+    }
+
+    /// Gets page `page` of the blogs with the tag `tag`, for a tag page. The
+    /// tag can be either its slug or its name. Pages start from 1, and `None`
+    /// is returned if there is no such page or no such tag
+    ///
+    /// The URLs of the pages are made from the `tag_root_slug` of the
+    /// `sitemap_options`
+    pub fn get_tag_page(
+        &self,
+        tag: &str,
+        page: usize,
+        pagination_options: &PaginationOptions,
+        sitemap_options: &SitemapOptions,
+    ) -> Option<Page<MediumBlogEntry>> {
+        let tag = self.tags.iter().find(|x| x.slug == tag || x.name == tag)?;
+
+        let slugs = self.tag_index.get(&tag.slug)?;
+
+        let tagged: Vec<&MediumBlogEntry> = slugs.iter().filter_map(|x| self.hash.get(x)).collect();
+
+        let root_slug = format!(
+            "{}/{}",
+            sitemap_options.tag_root_slug,
+            encode_slug(&tag.slug)
+        );
+
+        return paginate(
+            tagged.iter().copied(),
+            tagged.len(),
+            page,
+            &root_slug,
+            pagination_options,
+        );
+    }
+
+    /// Gets the blogs either side of the blog with the full slug `slug`, for
+    /// previous and next links. With a `tag`, either its slug or its name,
+    /// only the blogs with that tag are neighbours. `None` if there is no such
+    /// blog, or it does not have the tag
+    pub fn get_neighbours(
+        &self,
+        slug: &str,
+        tag: Option<&str>,
+    ) -> Option<Neighbours<&MediumBlogEntry>> {
+        let slugs = match tag {
+            Some(x) => {
+                let tag = self.tags.iter().find(|y| y.slug == x || y.name == x)?;
+                Some(self.tag_index.get(&tag.slug)?)
+            }
+            None => None,
+        };
+
+        return neighbours(&self.entries, &self.positions, slugs, slug);
+    }
+
+    /// Gets the number of blogs with each tag, from most to least used
+    pub fn get_tag_counts(&self) -> Vec<TagCount> {
+        let counts = self
+            .tags
+            .iter()
+            .map(|x| TagCount {
+                name: x.name.clone(),
+                slug: x.slug.clone(),
+                count: match self.tag_index.get(&x.slug) {
+                    Some(y) => y.len(),
+                    None => 0,
+                },
+            })
+            .collect();
+
+        return sorted_counts(counts);
+    }
+
+    /// Gets every tag weighted for a tag cloud, sorted alphabetically
+    pub fn get_tag_cloud(&self, options: &TagCloudOptions) -> Vec<TagWeight> {
+        return tag_cloud(&self.get_tag_counts(), options);
+    }
+
+    /// Gets the related blogs of the blog with the full slug `slug`, best
+    /// first. There are none unless `related` was set in the `BlogOptions`
+    pub fn get_related(&self, slug: &str) -> Vec<&MediumBlogEntry> {
+        return match self.related.get(slug) {
+            Some(x) => x.iter().filter_map(|y| self.hash.get(y)).collect(),
+            None => vec![],
+        };
+    }
+
+    /// Searches the blogs for `query`, best match first. Use the `slug` of
+    /// each result with `hash` to find the blog. There are no results unless
+    /// `search` was set in the `BlogOptions`
+    ///
+    /// Every word of the query must be found in a blog for it to match. Put
+    /// words in quotes to match them as a phrase e.g. `"static site"`, and end
+    /// a word with `*` to match every word starting with it e.g. `optim*`
+    pub fn search(&self, query: &str, options: &SearchOptions) -> Vec<SearchResult> {
+        return match &self.search_index {
+            Some(x) => x.search(query, options),
+            None => vec![],
+        };
+    }
+
+    /// Exports a search index of every blog, for searching in the browser. See
+    /// `SearchExport` for the format
+    ///
+    /// A `MediumBlogEntry` does not hold any HTML, so every blog is rendered.
+    /// This is best done once when the blog is deployed rather than on every
+    /// request
+    ///
+    /// The URLs of the blogs are made from the `blog_root_slug` of the
+    /// `sitemap_options`
+    pub fn export_search(
+        &self,
+        base: PathBuf,
+        export_options: &SearchExportOptions,
+        sitemap_options: &SitemapOptions,
+        blog_options: &BlogOptions,
+    ) -> Result<SearchExport, BlogError> {
+        let mut rendered = vec![];
+
+        for entry in &self.entries {
+            rendered.push(entry.render(base.clone(), blog_options)?);
+        }
+
+        return export_documents(&rendered, export_options, sitemap_options);
+    }
 }
 
 /// An individual blog post. You will need to render this using `render`
@@ -52,6 +275,8 @@ pub struct MediumBlogEntry {
     slug: String,
     /// `Vec` of tags for this blog
     tags: Vec<String>,
+    /// URL slugs of the tags, in the same order as `tags`
+    tag_slugs: Vec<String>,
     /// Table of contents
     toc: Option<String>,
     /// Optional `Vec` of keywords. Intended for SEO in comparison to tags
@@ -64,9 +289,23 @@ pub struct MediumBlogEntry {
     author_webpage: Option<String>,
     /// Preview of the blogpost, useful for showing on index pages
     preview: String,
-    file_name: String, // ! can't be present in `high` or `low`
+    /// HTML excerpt of the blogpost, if `excerpt_blocks` is set in the
+    /// `PreviewOptions`
+    excerpt: Option<String>,
+    /// Number of words, not counting code blocks
+    word_count: usize,
+    /// Estimated reading time in minutes
+    reading_time: usize,
+    /// Plain text of the blog, only while the search index is built and the
+    /// related blogs are found
+    #[serde(skip)]
+    text: Option<String>,
     last_modified: Option<NaiveDate>,
     priority: Option<f64>,
+    draft: bool,
+    publish_at: Option<NaiveDateTime>,
+    /// Any metadata which `blog-tools` does not use itself
+    extra: Map<String, Value>,
 }
 
 impl Blog for MediumBlogEntry {
@@ -74,40 +313,40 @@ impl Blog for MediumBlogEntry {
         blog: T,
         toc_generation_func: Option<&dyn Fn(&markdown::mdast::Node) -> String>,
         preview_chars: Option<usize>,
+        blog_options: &BlogOptions,
     ) -> Result<Self, BlogError> {
-        let json = get_json_data(&blog)?;
+        let (json, markdown) = get_blog_data(&blog, blog_options)?;
 
-        let markdown = match fs::read_to_string(&blog) {
-            Ok(x) => x,
-            Err(y) => return Err(BlogError::File(y)),
-        };
+        let html = render_markdown(&markdown, json.safety, blog_options)?;
 
-        let html = match to_html_with_options(
-            &markdown,
-            &Options {
-                compile: markdown::CompileOptions {
-                    allow_dangerous_html: true,
-                    allow_dangerous_protocol: true,
+        let preview: String =
+            get_preview(&html, &markdown, preview_chars, json.safety, blog_options)?;
 
-                    ..markdown::CompileOptions::default()
-                },
-                ..markdown::Options::default()
-            },
-        ) {
-            Ok(x) => x,
-            Err(y) => return Err(BlogError::Markdown(y)),
-        };
+        let toc = toc(&markdown, toc_generation_func, &blog_options.render)?;
 
-        let preview: String = get_preview(&html, preview_chars);
+        let excerpt = get_excerpt(&html, &markdown, json.safety, blog_options)?;
 
-        let toc = toc(&markdown, toc_generation_func)?;
+        let word_count = count_words(&markdown, &blog_options.render)?;
 
-        let file_name = match blog.as_ref().file_name() {
-            Some(x) => x.to_str().unwrap().to_string(),
-            None => return Err(BlogError::FileNotFound),
+        let reading_time = reading_time(word_count, blog_options);
+
+        // The text is only needed to build the search index and find the
+        // related blogs
+        let text = if blog_options.search.is_some() || blog_options.related.is_some() {
+            Some(html_text(&html))
+        } else {
+            None
         };
 
-        return Ok(MediumBlogEntry::new(json, toc, preview, file_name));
+        return Ok(MediumBlogEntry::new(
+            json,
+            toc,
+            preview,
+            excerpt,
+            word_count,
+            reading_time,
+            text,
+        ));
     }
 
     fn get_title(&self) -> String {
@@ -115,7 +354,7 @@ impl Blog for MediumBlogEntry {
     }
 
     fn get_date_listed(&self) -> NaiveDate {
-        return self.date.clone();
+        return self.date;
     }
 
     fn get_description(&self) -> Option<String> {
@@ -138,6 +377,10 @@ impl Blog for MediumBlogEntry {
         return self.tags.clone();
     }
 
+    fn get_tag_slugs(&self) -> Vec<String> {
+        return self.tag_slugs.clone();
+    }
+
     fn get_table_of_contents(&self) -> Option<String> {
         return self.toc.clone();
     }
@@ -162,12 +405,36 @@ impl Blog for MediumBlogEntry {
         return self.preview.clone();
     }
 
+    fn get_excerpt(&self) -> Option<String> {
+        return self.excerpt.clone();
+    }
+
+    fn get_word_count(&self) -> usize {
+        return self.word_count;
+    }
+
+    fn get_reading_time(&self) -> usize {
+        return self.reading_time;
+    }
+
     fn get_last_modified(&self) -> Option<NaiveDate> {
-        return self.last_modified.clone();
+        return self.last_modified;
     }
 
     fn get_priority(&self) -> Option<f64> {
-        return self.priority.clone();
+        return self.priority;
+    }
+
+    fn is_draft(&self) -> bool {
+        return self.draft;
+    }
+
+    fn get_publish_at(&self) -> Option<NaiveDateTime> {
+        return self.publish_at;
+    }
+
+    fn get_extra(&self) -> Map<String, Value> {
+        return self.extra.clone();
     }
 }
 
@@ -176,7 +443,10 @@ impl MediumBlogEntry {
         json: BlogJson,
         toc: Option<String>,
         preview: String,
-        file_name: String,
+        excerpt: Option<String>,
+        word_count: usize,
+        reading_time: usize,
+        text: Option<String>,
     ) -> Self {
         return MediumBlogEntry {
             title: json.title,
@@ -184,47 +454,46 @@ impl MediumBlogEntry {
             desc: json.desc,
             slug: json.slug,
             tags: json.tags,
+            tag_slugs: json.tag_slugs,
             toc: toc,
             keywords: json.keywords,
             canonical_link: json.canonical_link,
             author_name: json.author_name,
             author_webpage: json.author_webpage,
             preview: preview,
-            file_name,
+            excerpt: excerpt,
+            word_count: word_count,
+            reading_time: reading_time,
+            text: text,
             last_modified: json.last_modified,
             priority: json.priority,
+            draft: json.draft,
+            publish_at: json.publish_at,
+            extra: json.extra,
         };
     }
 
+    // Takes the plain text out of the entry, leaving it empty
+    pub(crate) fn take_text(&mut self) -> String {
+        return self.text.take().unwrap_or_default();
+    }
+
     /// Use this function to render a `MediumBlogEntry` into a `HighBlogEntry`,
     /// which then contains the full blog HTML you can return to a user
-    pub fn render(&self, base: PathBuf) -> Result<HighBlogEntry, BlogError> {
-        let year = self.date.year();
-        let path = base
-            .join(format!("{}", year))
-            .join(format!("{}", self.date))
-            .join(self.file_name.clone());
-
-        let md = match fs::read_to_string(path) {
-            Ok(x) => x,
-            Err(y) => return Err(BlogError::File(y)),
+    ///
+    /// The blog is found using the `layout` of the `blog_options`, so these
+    /// should be the same options the `MediumBlog` was created with
+    pub fn render(
+        &self,
+        base: PathBuf,
+        blog_options: &BlogOptions,
+    ) -> Result<HighBlogEntry, BlogError> {
+        let (json, md) = match find_blog(base, self.date, &self.slug, blog_options)? {
+            Some(x) => x,
+            None => return Err(BlogError::FileNotFound),
         };
 
-        let html = match to_html_with_options(
-            &md,
-            &Options {
-                compile: markdown::CompileOptions {
-                    allow_dangerous_html: true,
-                    allow_dangerous_protocol: true,
-
-                    ..markdown::CompileOptions::default()
-                },
-                ..markdown::Options::default()
-            },
-        ) {
-            Ok(x) => x,
-            Err(y) => return Err(BlogError::Markdown(y)),
-        };
+        let html = render_markdown(&md, json.safety, blog_options)?;
 
         let high = HighBlogEntry::new_from_medium(self, html);
 
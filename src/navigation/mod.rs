@@ -0,0 +1,5 @@
+mod navigation;
+mod types;
+pub use types::Neighbours;
+
+pub(crate) use navigation::{neighbours, positions};
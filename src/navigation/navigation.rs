@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use crate::types::Blog;
+
+use super::types::Neighbours;
+
+/// The position of every blog in `entries`, by its full slug
+pub fn positions<T: Blog>(entries: &[T]) -> HashMap<String, usize> {
+    return entries
+        .iter()
+        .enumerate()
+        .map(|(i, x)| (x.get_full_slug(), i))
+        .collect();
+}
+
+/// The blogs either side of the blog with the full slug `slug`. `entries`
+/// should be newest first. With `slugs`, only the blogs in it are neighbours -
+/// these must be in the same order as `entries`, like the tag index is. `None`
+/// if there is no such blog, or it is not in `slugs`
+pub fn neighbours<'a, T>(
+    entries: &'a [T],
+    positions: &HashMap<String, usize>,
+    slugs: Option<&Vec<String>>,
+    slug: &str,
+) -> Option<Neighbours<&'a T>> {
+    let position = *positions.get(slug)?;
+
+    let slugs = match slugs {
+        Some(x) => x,
+        None => {
+            return Some(Neighbours {
+                prev: entries.get(position + 1),
+                next: position.checked_sub(1).and_then(|x| entries.get(x)),
+            })
+        }
+    };
+
+    // The slugs are in the same order as the entries, so are sorted by position
+    let index = slugs
+        .binary_search_by_key(&Some(position), |x| positions.get(x).copied())
+        .ok()?;
+
+    let entry = |i: usize| -> Option<&'a T> {
+        let slug = slugs.get(i)?;
+        return entries.get(*positions.get(slug)?);
+    };
+
+    return Some(Neighbours {
+        prev: entry(index + 1),
+        next: index.checked_sub(1).and_then(entry),
+    });
+}
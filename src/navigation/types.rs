@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// The blogs either side of a blog, for previous and next links
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Neighbours<T> {
+    /// The blog published just before, if there is one
+    pub prev: Option<T>,
+    /// The blog published just after, if there is one
+    pub next: Option<T>,
+}
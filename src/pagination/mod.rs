@@ -0,0 +1,5 @@
+mod pagination;
+mod types;
+pub use types::{Page, PaginationOptions};
+
+pub(crate) use pagination::{new_page, page_url, paginate, total_pages};
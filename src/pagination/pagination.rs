@@ -0,0 +1,144 @@
+use super::types::{Page, PaginationOptions};
+
+/// Takes page `page` of the blogs, which should already be sorted. Returns
+/// `None` if there is no such page
+pub fn paginate<'a, T: Clone + 'a>(
+    entries: impl IntoIterator<Item = &'a T>,
+    total_items: usize,
+    page: usize,
+    root_slug: &str,
+    options: &PaginationOptions,
+) -> Option<Page<T>> {
+    if page == 0 || page > total_pages(total_items, options) {
+        return None;
+    }
+
+    let page_size = options.page_size.max(1);
+
+    let items = entries
+        .into_iter()
+        .skip((page - 1) * page_size)
+        .take(page_size)
+        .cloned()
+        .collect();
+
+    return Some(new_page(items, page, total_items, root_slug, options));
+}
+
+/// Wraps the blogs of page `page` with the details of the page
+pub fn new_page<T>(
+    items: Vec<T>,
+    page: usize,
+    total_items: usize,
+    root_slug: &str,
+    options: &PaginationOptions,
+) -> Page<T> {
+    let total_pages = total_pages(total_items, options);
+
+    let has_next = page < total_pages;
+    let has_prev = page > 1;
+
+    let next_url = if has_next {
+        Some(format!("/{}", page_url(root_slug, page + 1, options)))
+    } else {
+        None
+    };
+
+    let prev_url = if has_prev {
+        Some(format!("/{}", page_url(root_slug, page - 1, options)))
+    } else {
+        None
+    };
+
+    return Page {
+        items: items,
+        page: page,
+        page_size: options.page_size.max(1),
+        total_items: total_items,
+        total_pages: total_pages,
+        has_next: has_next,
+        has_prev: has_prev,
+        url: format!("/{}", page_url(root_slug, page, options)),
+        next_url: next_url,
+        prev_url: prev_url,
+    };
+}
+
+/// The number of pages needed for `total_items` blogs, which is never less
+/// than 1
+pub fn total_pages(total_items: usize, options: &PaginationOptions) -> usize {
+    return total_items.div_ceil(options.page_size.max(1)).max(1);
+}
+
+/// The URL of page `page`, without the leading `/`. The first page is the
+/// root itself
+pub fn page_url(root_slug: &str, page: usize, options: &PaginationOptions) -> String {
+    if page <= 1 {
+        return root_slug.to_string();
+    }
+
+    return format!("{}/{}/{}", root_slug, options.page_slug, page);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{page_url, paginate, total_pages, PaginationOptions};
+
+    fn options(page_size: usize) -> PaginationOptions {
+        return PaginationOptions {
+            page_size: page_size,
+            ..PaginationOptions::default()
+        };
+    }
+
+    #[test]
+    fn counts_pages() {
+        assert_eq!(total_pages(0, &options(10)), 1);
+        assert_eq!(total_pages(10, &options(10)), 1);
+        assert_eq!(total_pages(11, &options(10)), 2);
+        assert_eq!(total_pages(3, &options(0)), 3);
+    }
+
+    #[test]
+    fn pages_have_bounds() {
+        let items: Vec<usize> = (1..=5).collect();
+        let options = options(2);
+
+        let first = paginate(&items, items.len(), 1, "blog", &options).unwrap();
+        assert_eq!(first.items, vec![1, 2]);
+        assert_eq!(first.url, "/blog");
+        assert_eq!(first.prev_url, None);
+        assert_eq!(first.next_url.as_deref(), Some("/blog/page/2"));
+
+        let last = paginate(&items, items.len(), 3, "blog", &options).unwrap();
+        assert_eq!(last.items, vec![5]);
+        assert_eq!(last.total_pages, 3);
+        assert!(last.has_prev && !last.has_next);
+        assert_eq!(last.prev_url.as_deref(), Some("/blog/page/2"));
+
+        assert!(paginate(&items, items.len(), 0, "blog", &options).is_none());
+        assert!(paginate(&items, items.len(), 4, "blog", &options).is_none());
+    }
+
+    #[test]
+    fn empty_blog_has_one_page() {
+        let items: Vec<usize> = vec![];
+
+        let page = paginate(&items, 0, 1, "blog", &options(10)).unwrap();
+        assert!(page.items.is_empty());
+        assert!(!page.has_next && !page.has_prev);
+
+        assert!(paginate(&items, 0, 2, "blog", &options(10)).is_none());
+    }
+
+    #[test]
+    fn first_page_is_the_root() {
+        let options = options(10);
+
+        assert_eq!(page_url("blog/tag/rust", 1, &options), "blog/tag/rust");
+        assert_eq!(
+            page_url("blog/tag/rust", 2, &options),
+            "blog/tag/rust/page/2"
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+/// A single page of blogs, for an index page or a tag page
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Page<T> {
+    /// The blogs on this page, newest first
+    pub items: Vec<T>,
+    /// The number of this page. The first page is 1
+    pub page: usize,
+    /// The most blogs there can be on a page
+    pub page_size: usize,
+    /// The number of blogs across every page
+    pub total_items: usize,
+    /// The number of pages. There is always at least one page, even when there
+    /// are no blogs
+    pub total_pages: usize,
+    /// Whether there is a page after this one
+    pub has_next: bool,
+    /// Whether there is a page before this one
+    pub has_prev: bool,
+    /// The URL path of this page e.g. `/blog/page/3`. The first page is the
+    /// index itself e.g. `/blog`
+    pub url: String,
+    /// The URL path of the next page, if there is one
+    pub next_url: Option<String>,
+    /// The URL path of the previous page, if there is one
+    pub prev_url: Option<String>,
+}
+
+/// Options to configure how blogs are split into pages
+pub struct PaginationOptions {
+    /// The number of blogs on each page. Default is 10
+    pub page_size: usize,
+    /// The part of the URL before the page number. The default is `page`,
+    /// which would mean the second page of the blog is found at
+    /// `www.example.com/blog/page/2` and the second page of the `science` tag
+    /// at `www.example.com/blog/tag/science/page/2`
+    pub page_slug: String,
+}
+
+impl Default for PaginationOptions {
+    fn default() -> Self {
+        Self {
+            page_size: 10,
+            page_slug: "page".to_string(),
+        }
+    }
+}
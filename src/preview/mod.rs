@@ -0,0 +1,5 @@
+mod preview;
+mod types;
+pub use types::{PreviewBoundary, PreviewOptions};
+
+pub(crate) use preview::{get_excerpt, get_preview, strip_excerpt_marker};
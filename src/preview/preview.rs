@@ -0,0 +1,350 @@
+use std::{borrow::Borrow, ops::Range};
+
+use markdown::to_mdast;
+
+use crate::{
+    common::{decode_html, escape_html, render_markdown, BlogError},
+    render::HtmlSafety,
+    types::BlogOptions,
+};
+
+use super::types::{PreviewBoundary, PreviewOptions};
+
+/// Makes the plain text preview of a blog. If the markdown has an excerpt
+/// marker then the preview is everything above it, otherwise it is the text of
+/// the paragraphs in the HTML, cut down to `preview_chars` characters. Default
+/// is 320 characters. The preview is HTML escaped
+pub fn get_preview(
+    html: &str,
+    markdown: &str,
+    preview_chars: Option<usize>,
+    safety: Option<HtmlSafety>,
+    blog_options: &BlogOptions,
+) -> Result<String, BlogError> {
+    let options = &blog_options.preview;
+
+    if let Some(excerpt) = excerpt_markdown(markdown, blog_options) {
+        let excerpt_html = render_markdown(excerpt, safety, blog_options)?;
+        return Ok(escape_html(&paragraph_text(&excerpt_html)));
+    }
+
+    let num_chars = preview_chars.unwrap_or(320);
+
+    let text = paragraph_text(html);
+
+    // The text is cut while decoded so every character counts once, then
+    // escaped again so none of it can become markup
+    return Ok(escape_html(&truncate(&text, num_chars, options)));
+}
+
+/// Removes the excerpt marker from the markdown, so it doesn't end up in the
+/// rendered blog
+pub fn strip_excerpt_marker(markdown: &str, blog_options: &BlogOptions) -> String {
+    let marker = match find_marker(markdown, blog_options) {
+        Some(x) => x,
+        None => return markdown.to_string(),
+    };
+
+    let rest = &markdown[marker.end..];
+
+    let rest = match rest.strip_prefix("\r\n") {
+        Some(x) => x,
+        None => rest.strip_prefix('\n').unwrap_or(rest),
+    };
+
+    return format!("{}{}", &markdown[..marker.start], rest);
+}
+
+/// Makes the HTML excerpt of a blog, if `excerpt_blocks` is set. If the
+/// markdown has an excerpt marker then the excerpt is everything above it,
+/// otherwise it is the first `excerpt_blocks` block elements of the HTML
+pub fn get_excerpt(
+    html: &str,
+    markdown: &str,
+    safety: Option<HtmlSafety>,
+    blog_options: &BlogOptions,
+) -> Result<Option<String>, BlogError> {
+    let options = &blog_options.preview;
+
+    let num_blocks = match options.excerpt_blocks {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+
+    if let Some(excerpt) = excerpt_markdown(markdown, blog_options) {
+        let excerpt_html = render_markdown(excerpt, safety, blog_options)?;
+        return Ok(Some(excerpt_html.trim().to_string()));
+    }
+
+    return Ok(Some(first_blocks(html, num_blocks)));
+}
+
+// Everything above the excerpt marker, if there is one
+fn excerpt_markdown<'a>(markdown: &'a str, blog_options: &BlogOptions) -> Option<&'a str> {
+    let marker = find_marker(markdown, blog_options)?;
+
+    return Some(&markdown[..marker.start]);
+}
+
+// Where the first excerpt marker is in the markdown. Only a marker which is a
+// block of its own counts, so one inside a code block is left alone
+fn find_marker(markdown: &str, blog_options: &BlogOptions) -> Option<Range<usize>> {
+    let marker = match &blog_options.preview.excerpt_marker {
+        Some(x) => x.trim(),
+        None => return None,
+    };
+
+    // Most blogs have no marker, so they don't need parsing again
+    if marker.is_empty() || !markdown.contains(marker) {
+        return None;
+    }
+
+    let parse_options = blog_options.render.markdown_options(None).parse;
+
+    let mdast = match to_mdast(markdown, &parse_options) {
+        Ok(x) => x,
+        Err(_) => return None,
+    };
+
+    for node in mdast.children()? {
+        let position = match node.position() {
+            Some(x) => x,
+            None => continue,
+        };
+
+        let range = position.start.offset..position.end.offset;
+
+        if markdown[range.clone()].trim() == marker {
+            return Some(range);
+        }
+    }
+
+    return None;
+}
+
+// The first `num_blocks` top level elements. Each element is written out again
+// from the parsed HTML, so any tags left open are closed
+fn first_blocks(html: &str, num_blocks: usize) -> String {
+    let dom = match tl::parse(html, tl::ParserOptions::default()) {
+        Ok(x) => x,
+        Err(_) => return String::new(),
+    };
+
+    let parser = dom.parser();
+
+    let mut blocks = vec![];
+
+    for handle in dom.children() {
+        if blocks.len() >= num_blocks {
+            break;
+        }
+
+        let node = match handle.get(parser) {
+            Some(x) => x,
+            None => continue,
+        };
+
+        // Skips the text between elements, and comments
+        if node.as_tag().is_none() {
+            continue;
+        }
+
+        blocks.push(node.outer_html(parser).to_string());
+    }
+
+    return blocks.join("\n");
+}
+
+// The text of every paragraph, separated by spaces
+fn paragraph_text(html: &str) -> String {
+    let dom = match tl::parse(html, tl::ParserOptions::default()) {
+        Ok(x) => x,
+        Err(_) => return String::new(),
+    };
+
+    let parser = dom.parser();
+
+    let mut paragraphs: Vec<String> = vec![];
+
+    for node in dom.nodes() {
+        let tag = match node.as_tag() {
+            Some(x) => x,
+            None => continue,
+        };
+
+        if tag.name() == "p" {
+            // `inner_text` leaves the entities as they are in the HTML
+            let text = decode_html(node.inner_text(parser).borrow());
+            paragraphs.push(text.trim().to_string());
+        }
+    }
+
+    return paragraphs.join(" ");
+}
+
+// Cuts the text down to `num_chars` characters at the boundary, adding the
+// ellipsis if anything was cut
+fn truncate(text: &str, num_chars: usize, options: &PreviewOptions) -> String {
+    let cut = match text.char_indices().nth(num_chars) {
+        Some((x, _)) => x,
+        None => return text.to_string(),
+    };
+
+    let head = &text[..cut];
+
+    // Whether the character after the cut starts a new word
+    let at_word_end = match text[cut..].chars().next() {
+        Some(x) => x.is_whitespace(),
+        None => true,
+    };
+
+    let sentence_end = match options.boundary {
+        PreviewBoundary::Sentence => last_sentence_end(text, cut),
+        PreviewBoundary::Word => None,
+    };
+
+    let head = match sentence_end {
+        Some(x) => &head[..x],
+        None if at_word_end => head,
+        None => match head.rfind(char::is_whitespace) {
+            Some(x) => &head[..x],
+            // A single long word, or a language without spaces
+            None => head,
+        },
+    };
+
+    return format!("{}{}", head.trim_end(), options.ellipsis);
+}
+
+// The end of the last sentence which finishes before `cut`
+fn last_sentence_end(text: &str, cut: usize) -> Option<usize> {
+    let mut end = None;
+
+    let mut chars = text[..cut].char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let next = match chars.peek() {
+            Some((_, x)) => Some(*x),
+            None => text[cut..].chars().next(),
+        };
+
+        let is_end = match c {
+            // These are followed by a space at the end of a sentence
+            '.' | '!' | '?' => match next {
+                Some(x) => x.is_whitespace(),
+                None => true,
+            },
+            '。' | '！' | '？' => true,
+            _ => false,
+        };
+
+        if is_end {
+            end = Some(i + c.len_utf8());
+        }
+    }
+
+    return end;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{common::render_markdown, render::HtmlSafety, types::BlogOptions};
+
+    use super::{
+        excerpt_markdown, get_preview, strip_excerpt_marker, truncate, PreviewBoundary,
+        PreviewOptions,
+    };
+
+    fn preview(markdown: &str, chars: usize, blog_options: &BlogOptions) -> String {
+        let html = render_markdown(markdown, None, blog_options).unwrap();
+        return get_preview(&html, markdown, Some(chars), None, blog_options).unwrap();
+    }
+
+    #[test]
+    fn truncates_multi_byte_text() {
+        let options = PreviewOptions::default();
+
+        assert_eq!(truncate("Grüße aus Köln", 8, &options), "Grüße…");
+        assert_eq!(truncate("東京は大きい都市です", 4, &options), "東京は大…");
+        assert_eq!(truncate("short", 10, &options), "short");
+    }
+
+    #[test]
+    fn truncates_at_word_and_sentence_boundaries() {
+        let mut options = PreviewOptions::default();
+
+        let text = "One two. Three four five six.";
+
+        assert_eq!(truncate(text, 11, &options), "One two.…");
+        assert_eq!(truncate(text, 14, &options), "One two. Three…");
+
+        options.boundary = PreviewBoundary::Sentence;
+        assert_eq!(truncate(text, 20, &options), "One two.…");
+        // Not even one sentence fits, so the last whole word is kept
+        assert_eq!(truncate(text, 5, &options), "One…");
+    }
+
+    #[test]
+    fn safe_preview_stays_escaped() {
+        let mut blog_options = BlogOptions::default();
+        blog_options.render.safety = HtmlSafety::Safe;
+
+        let markdown = "Hello <script>alert(1)</script> world";
+
+        let html = render_markdown(markdown, None, &blog_options).unwrap();
+        assert!(!html.contains("<script>"));
+
+        let preview = preview(markdown, 320, &blog_options);
+        assert!(!preview.contains('<'));
+        assert!(preview.contains("&lt;script&gt;"));
+    }
+
+    #[test]
+    fn preview_is_escaped_after_truncating() {
+        let blog_options = BlogOptions::default();
+
+        assert_eq!(
+            preview("Fish &amp; chips are great", 12, &blog_options),
+            "Fish &amp; chips…"
+        );
+    }
+
+    #[test]
+    fn marker_ends_the_preview() {
+        let blog_options = BlogOptions::default();
+
+        let markdown = "First paragraph.\n\n<!-- more -->\n\nSecond paragraph.\n";
+
+        assert_eq!(preview(markdown, 320, &blog_options), "First paragraph.");
+        assert_eq!(
+            strip_excerpt_marker(markdown, &blog_options),
+            "First paragraph.\n\n\nSecond paragraph.\n"
+        );
+    }
+
+    #[test]
+    fn marker_in_code_block_is_kept() {
+        let blog_options = BlogOptions::default();
+
+        let markdown = "Text.\n\n```html\n<!-- more -->\n```\n";
+
+        assert_eq!(excerpt_markdown(markdown, &blog_options), None);
+        assert_eq!(strip_excerpt_marker(markdown, &blog_options), markdown);
+
+        let html = render_markdown(markdown, None, &blog_options).unwrap();
+        assert!(html.contains("&lt;!-- more --&gt;"));
+    }
+
+    #[test]
+    fn only_first_marker_is_stripped() {
+        let blog_options = BlogOptions::default();
+
+        let markdown = "A.\n\n<!-- more -->\n\nB.\n\n<!-- more -->\n";
+
+        assert_eq!(excerpt_markdown(markdown, &blog_options), Some("A.\n\n"));
+        assert_eq!(
+            strip_excerpt_marker(markdown, &blog_options),
+            "A.\n\n\nB.\n\n<!-- more -->\n"
+        );
+    }
+}
@@ -0,0 +1,40 @@
+/// Options to configure how the plain text previews of blogs are made
+pub struct PreviewOptions {
+    /// Where a preview which is too long is cut. The default is `Word`
+    pub boundary: PreviewBoundary,
+    /// Added to the end of a preview which has been cut. The default is `…`
+    pub ellipsis: String,
+    /// A line in the markdown which marks the end of the preview, such as
+    /// `<!-- more -->`. Everything above the marker is the preview, whatever
+    /// its length, and the marker itself is removed from the rendered blog.
+    /// The default is `Some("<!-- more -->")` - set to `None` to ignore it
+    pub excerpt_marker: Option<String>,
+    /// How many block elements, such as paragraphs, lists and code blocks, are
+    /// kept in the HTML excerpt. Unlike the preview, the excerpt keeps links,
+    /// emphasis and the like. If there is an excerpt marker then the excerpt is
+    /// everything above it instead. The default is `None`, meaning there is no
+    /// HTML excerpt
+    pub excerpt_blocks: Option<usize>,
+}
+
+impl Default for PreviewOptions {
+    fn default() -> Self {
+        Self {
+            boundary: PreviewBoundary::Word,
+            ellipsis: "…".to_string(),
+            excerpt_marker: Some("<!-- more -->".to_string()),
+            excerpt_blocks: None,
+        }
+    }
+}
+
+/// Where a preview which is too long is cut
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PreviewBoundary {
+    /// At the end of the last whole word which fits. Text without spaces, such
+    /// as Japanese, is cut at the last character which fits
+    Word,
+    /// At the end of the last whole sentence which fits, or the last whole
+    /// word if not even one sentence fits
+    Sentence,
+}
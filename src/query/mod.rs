@@ -0,0 +1,3 @@
+mod query;
+mod types;
+pub use types::{Query, SortBy, SortOrder};
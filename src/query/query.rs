@@ -0,0 +1,47 @@
+use std::cmp::Ordering;
+
+use chrono::NaiveDate;
+
+use crate::types::Blog;
+
+use super::types::SortBy;
+
+/// Whether the blog has the tag, given as either its slug or its name
+pub fn has_tag<T: Blog>(entry: &T, tag: &str) -> bool {
+    return entry.get_tag_slugs().iter().any(|x| x == tag)
+        || entry.get_tags().iter().any(|x| x == tag);
+}
+
+/// Whether the blog has the keyword, ignoring case
+pub fn has_keyword<T: Blog>(entry: &T, keyword: &str) -> bool {
+    let keyword = keyword.trim().to_lowercase();
+
+    return match entry.get_keywords() {
+        Some(x) => x.iter().any(|y| y.trim().to_lowercase() == keyword),
+        None => false,
+    };
+}
+
+/// Compares two blogs in ascending order
+pub fn compare<T: Blog>(a: &T, b: &T, sort: SortBy) -> Ordering {
+    return match sort {
+        SortBy::Date => a.get_date_listed().cmp(&b.get_date_listed()),
+        SortBy::LastModified => last_modified(a).cmp(&last_modified(b)),
+        SortBy::Title => a
+            .get_title()
+            .to_lowercase()
+            .cmp(&b.get_title().to_lowercase()),
+        SortBy::Priority => priority(a).total_cmp(&priority(b)),
+    };
+}
+
+fn last_modified<T: Blog>(entry: &T) -> NaiveDate {
+    return match entry.get_last_modified() {
+        Some(x) => x,
+        None => entry.get_date_listed(),
+    };
+}
+
+fn priority<T: Blog>(entry: &T) -> f64 {
+    return entry.get_priority().unwrap_or(f64::MIN);
+}
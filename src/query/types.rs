@@ -0,0 +1,375 @@
+use chrono::NaiveDate;
+
+use crate::{
+    pagination::{paginate, Page, PaginationOptions},
+    types::Blog,
+};
+
+use super::query::{compare, has_keyword, has_tag};
+
+// A filter given to `filter`
+type Predicate<'a, T> = Box<dyn Fn(&T) -> bool + 'a>;
+
+/// A query over the blogs, which filters and sorts them without copying any.
+/// Start one with `query` in `high` or `medium`, add filters and a sort, then
+/// finish it with `results` or `page`
+///
+/// ```rust,ignore
+/// let blogs = STATIC_BLOG_ENTRIES
+///     .query()
+///     .author("Indigo Curnick")
+///     .from(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap())
+///     .until(NaiveDate::from_ymd_opt(2024, 12, 31).unwrap())
+///     .any_tags(&["rust", "wasm"])
+///     .sort_by(SortBy::LastModified, SortOrder::Descending)
+///     .results();
+/// ```
+pub struct Query<'a, T: Blog> {
+    entries: &'a [T],
+    all_tags: Vec<String>,
+    any_tags: Vec<Vec<String>>,
+    none_tags: Vec<String>,
+    author: Option<String>,
+    from: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+    keywords: Vec<String>,
+    predicates: Vec<Predicate<'a, T>>,
+    sort: SortBy,
+    order: SortOrder,
+}
+
+impl<'a, T: Blog> Query<'a, T> {
+    /// A query over `entries`, which should be newest first. With no filters
+    /// this matches every blog, newest first
+    pub fn new(entries: &'a [T]) -> Self {
+        return Query {
+            entries: entries,
+            all_tags: vec![],
+            any_tags: vec![],
+            none_tags: vec![],
+            author: None,
+            from: None,
+            until: None,
+            keywords: vec![],
+            predicates: vec![],
+            sort: SortBy::Date,
+            order: SortOrder::Descending,
+        };
+    }
+
+    /// Only blogs with every one of these tags. Each tag can be either its
+    /// slug or its name
+    pub fn all_tags<S: AsRef<str>>(mut self, tags: &[S]) -> Self {
+        self.all_tags
+            .extend(tags.iter().map(|x| x.as_ref().to_string()));
+        return self;
+    }
+
+    /// Only blogs with at least one of these tags. Each tag can be either its
+    /// slug or its name. Using this more than once means a blog must have at
+    /// least one tag from each
+    pub fn any_tags<S: AsRef<str>>(mut self, tags: &[S]) -> Self {
+        self.any_tags
+            .push(tags.iter().map(|x| x.as_ref().to_string()).collect());
+        return self;
+    }
+
+    /// Only blogs with none of these tags. Each tag can be either its slug or
+    /// its name
+    pub fn none_tags<S: AsRef<str>>(mut self, tags: &[S]) -> Self {
+        self.none_tags
+            .extend(tags.iter().map(|x| x.as_ref().to_string()));
+        return self;
+    }
+
+    /// Only blogs by this author
+    pub fn author(mut self, author: &str) -> Self {
+        self.author = Some(author.to_string());
+        return self;
+    }
+
+    /// Only blogs published on or after this date
+    pub fn from(mut self, date: NaiveDate) -> Self {
+        self.from = Some(date);
+        return self;
+    }
+
+    /// Only blogs published on or before this date
+    pub fn until(mut self, date: NaiveDate) -> Self {
+        self.until = Some(date);
+        return self;
+    }
+
+    /// Only blogs with this keyword, ignoring case. Using this more than once
+    /// means a blog must have every keyword
+    pub fn keyword(mut self, keyword: &str) -> Self {
+        self.keywords.push(keyword.to_string());
+        return self;
+    }
+
+    /// Only blogs for which `predicate` is `true`, for anything the other
+    /// filters can't do such as filtering on `extra`
+    pub fn filter<F: Fn(&T) -> bool + 'a>(mut self, predicate: F) -> Self {
+        self.predicates.push(Box::new(predicate));
+        return self;
+    }
+
+    /// How the blogs are sorted. The default is by date, newest first. Blogs
+    /// which are the same keep their order from `entries`
+    pub fn sort_by(mut self, sort: SortBy, order: SortOrder) -> Self {
+        self.sort = sort;
+        self.order = order;
+        return self;
+    }
+
+    /// Every blog which matches the query, sorted
+    pub fn results(&self) -> Vec<&'a T> {
+        let mut results: Vec<&'a T> = self.entries.iter().filter(|x| self.matches(x)).collect();
+
+        results.sort_by(|a, b| {
+            let ordering = compare(*a, *b, self.sort);
+
+            return match self.order {
+                SortOrder::Ascending => ordering,
+                SortOrder::Descending => ordering.reverse(),
+            };
+        });
+
+        return results;
+    }
+
+    /// Page `page` of the blogs which match the query, sorted. Pages start
+    /// from 1, and `None` is returned if there is no such page. The URLs of
+    /// the pages are made from `root_slug` e.g. `blog/rust-in-2024`
+    pub fn page(
+        &self,
+        page: usize,
+        pagination_options: &PaginationOptions,
+        root_slug: &str,
+    ) -> Option<Page<&'a T>> {
+        let results = self.results();
+
+        return paginate(&results, results.len(), page, root_slug, pagination_options);
+    }
+
+    fn matches(&self, entry: &T) -> bool {
+        let date = entry.get_date_listed();
+
+        if let Some(x) = self.from {
+            if date < x {
+                return false;
+            }
+        }
+
+        if let Some(x) = self.until {
+            if date > x {
+                return false;
+            }
+        }
+
+        if let Some(x) = &self.author {
+            if entry.get_author_name().as_ref() != Some(x) {
+                return false;
+            }
+        }
+
+        if !self.all_tags.iter().all(|x| has_tag(entry, x)) {
+            return false;
+        }
+
+        if !self
+            .any_tags
+            .iter()
+            .all(|x| x.iter().any(|y| has_tag(entry, y)))
+        {
+            return false;
+        }
+
+        if self.none_tags.iter().any(|x| has_tag(entry, x)) {
+            return false;
+        }
+
+        if !self.keywords.iter().all(|x| has_keyword(entry, x)) {
+            return false;
+        }
+
+        return self.predicates.iter().all(|x| x(entry));
+    }
+}
+
+/// What the blogs of a query are sorted by
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortBy {
+    /// The date the blog was published
+    Date,
+    /// The date the blog was last modified, or the date it was published if it
+    /// has never been modified
+    LastModified,
+    /// The title, ignoring case
+    Title,
+    /// The sitemap priority. Blogs without a priority are sorted as the lowest
+    Priority,
+}
+
+/// Which way the blogs of a query are sorted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortOrder {
+    /// Oldest, first alphabetically or lowest priority first
+    Ascending,
+    /// Newest, last alphabetically or highest priority first
+    Descending,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use serde_json::json;
+
+    use super::{Query, SortBy, SortOrder};
+    use crate::{
+        low::LowBlogEntry, pagination::PaginationOptions, testing::low_entry, types::Blog,
+    };
+
+    fn blog(title: &str, date: &str, tags: &[&str], author: &str, priority: f64) -> LowBlogEntry {
+        return low_entry(json!({
+            "title": title,
+            "date": date,
+            "slug": title.to_lowercase(),
+            "tags": tags,
+            "keywords": ["Blog", title],
+            "author_name": author,
+            "priority": priority,
+        }));
+    }
+
+    // Newest first, as in `high` and `medium`
+    fn blogs() -> Vec<LowBlogEntry> {
+        return vec![
+            blog("Delta", "2024-04-01", &["Rust", "Wasm"], "Ann", 0.4),
+            blog("Charlie", "2024-03-01", &["Rust"], "Bob", 0.9),
+            blog("Bravo", "2024-02-01", &["Python"], "Ann", 0.1),
+            blog("Alpha", "2024-01-01", &["Wasm", "Python"], "Bob", 0.5),
+        ];
+    }
+
+    fn titles(results: Vec<&LowBlogEntry>) -> Vec<String> {
+        return results.iter().map(|x| x.get_title()).collect();
+    }
+
+    fn date(text: &str) -> NaiveDate {
+        return text.parse().unwrap();
+    }
+
+    #[test]
+    fn no_filters_match_everything_newest_first() {
+        let blogs = blogs();
+
+        assert_eq!(
+            titles(Query::new(&blogs).results()),
+            vec!["Delta", "Charlie", "Bravo", "Alpha"]
+        );
+    }
+
+    #[test]
+    fn filters_by_tags() {
+        let blogs = blogs();
+
+        assert_eq!(
+            titles(Query::new(&blogs).all_tags(&["rust", "Wasm"]).results()),
+            vec!["Delta"]
+        );
+        assert_eq!(
+            titles(Query::new(&blogs).any_tags(&["wasm", "python"]).results()),
+            vec!["Delta", "Bravo", "Alpha"]
+        );
+        assert_eq!(
+            titles(
+                Query::new(&blogs)
+                    .any_tags(&["rust", "python"])
+                    .any_tags(&["wasm"])
+                    .results()
+            ),
+            vec!["Delta", "Alpha"]
+        );
+        assert_eq!(
+            titles(Query::new(&blogs).none_tags(&["rust"]).results()),
+            vec!["Bravo", "Alpha"]
+        );
+    }
+
+    #[test]
+    fn filters_by_author_dates_keywords_and_predicates() {
+        let blogs = blogs();
+
+        assert_eq!(
+            titles(Query::new(&blogs).author("Ann").results()),
+            vec!["Delta", "Bravo"]
+        );
+        assert_eq!(
+            titles(
+                Query::new(&blogs)
+                    .from(date("2024-02-01"))
+                    .until(date("2024-03-01"))
+                    .results()
+            ),
+            vec!["Charlie", "Bravo"]
+        );
+        assert_eq!(
+            titles(
+                Query::new(&blogs)
+                    .keyword(" blog ")
+                    .keyword("ALPHA")
+                    .results()
+            ),
+            vec!["Alpha"]
+        );
+        assert_eq!(
+            titles(
+                Query::new(&blogs)
+                    .filter(|x| x.get_title().starts_with('C'))
+                    .results()
+            ),
+            vec!["Charlie"]
+        );
+    }
+
+    #[test]
+    fn sorts_results() {
+        let blogs = blogs();
+
+        assert_eq!(
+            titles(
+                Query::new(&blogs)
+                    .sort_by(SortBy::Title, SortOrder::Ascending)
+                    .results()
+            ),
+            vec!["Alpha", "Bravo", "Charlie", "Delta"]
+        );
+        assert_eq!(
+            titles(
+                Query::new(&blogs)
+                    .sort_by(SortBy::Priority, SortOrder::Descending)
+                    .results()
+            ),
+            vec!["Charlie", "Alpha", "Delta", "Bravo"]
+        );
+    }
+
+    #[test]
+    fn pages_results() {
+        let blogs = blogs();
+        let options = PaginationOptions {
+            page_size: 1,
+            ..PaginationOptions::default()
+        };
+
+        let query = Query::new(&blogs).author("Bob");
+        let page = query.page(2, &options, "blog/bob").unwrap();
+
+        assert_eq!(page.total_pages, 2);
+        assert_eq!(page.items[0].get_title(), "Alpha");
+        assert_eq!(page.url, "/blog/bob/page/2");
+        assert!(query.page(3, &options, "blog/bob").is_none());
+    }
+}
@@ -0,0 +1,5 @@
+mod related;
+mod types;
+pub use types::RelatedOptions;
+
+pub(crate) use related::{profile, related_index, top_related};
@@ -0,0 +1,150 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{search::terms, types::Blog};
+
+use super::types::{Profile, RelatedOptions};
+
+/// The full slugs of the related blogs of every blog, by the full slug of
+/// each blog. `texts` is the plain text of each blog, in the same order as
+/// `entries`
+pub fn related_index<T: Blog>(
+    entries: &[T],
+    texts: &[String],
+    options: &RelatedOptions,
+) -> HashMap<String, Vec<String>> {
+    let profiles: Vec<Profile> = entries
+        .iter()
+        .zip(texts)
+        .map(|(entry, text)| profile(entry.get_tag_slugs(), entry.get_keywords(), text))
+        .collect();
+
+    let vectors = tf_idf(&profiles);
+
+    let mut related = HashMap::new();
+
+    for (i, entry) in entries.iter().enumerate() {
+        let slugs = rank(&profiles, &vectors, i, options)
+            .into_iter()
+            .map(|x| entries[x].get_full_slug())
+            .collect();
+
+        related.insert(entry.get_full_slug(), slugs);
+    }
+
+    return related;
+}
+
+/// Everything about a single blog which is used to score how related it is
+pub fn profile(tag_slugs: Vec<String>, keywords: Option<Vec<String>>, text: &str) -> Profile {
+    let keywords = match keywords {
+        Some(x) => x.iter().map(|y| y.trim().to_lowercase()).collect(),
+        None => HashSet::new(),
+    };
+
+    let mut counts = HashMap::new();
+
+    for term in terms(text) {
+        *counts.entry(term).or_insert(0) += 1;
+    }
+
+    return Profile {
+        tags: tag_slugs.into_iter().collect(),
+        keywords: keywords,
+        counts: counts,
+    };
+}
+
+/// The indexes of the blogs most related to the blog at `index`, best first.
+/// Blogs which are not related at all are left out, and equal blogs keep the
+/// order of `profiles`
+pub fn top_related(profiles: &[Profile], index: usize, options: &RelatedOptions) -> Vec<usize> {
+    return rank(profiles, &tf_idf(profiles), index, options);
+}
+
+fn rank(
+    profiles: &[Profile],
+    vectors: &[HashMap<&str, f64>],
+    index: usize,
+    options: &RelatedOptions,
+) -> Vec<usize> {
+    let mut scores = vec![];
+
+    for (i, other) in profiles.iter().enumerate() {
+        if i == index {
+            continue;
+        }
+
+        let score = options.tag_weight * jaccard(&profiles[index].tags, &other.tags)
+            + options.keyword_weight * jaccard(&profiles[index].keywords, &other.keywords)
+            + options.text_weight * cosine(&vectors[index], &vectors[i]);
+
+        if score > 0.0 {
+            scores.push((i, score));
+        }
+    }
+
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    return scores
+        .into_iter()
+        .take(options.count)
+        .map(|(i, _)| i)
+        .collect();
+}
+
+// The TF-IDF vector of the text of every blog, scaled to a length of 1
+fn tf_idf(profiles: &[Profile]) -> Vec<HashMap<&str, f64>> {
+    let mut frequencies: HashMap<&str, usize> = HashMap::new();
+
+    for profile in profiles {
+        for term in profile.counts.keys() {
+            *frequencies.entry(term.as_str()).or_insert(0) += 1;
+        }
+    }
+
+    let total = profiles.len() as f64;
+
+    let mut vectors = vec![];
+
+    for profile in profiles {
+        let mut vector = HashMap::new();
+
+        for (term, count) in &profile.counts {
+            // A term in every blog says nothing about which blogs are related
+            let idf = (total / frequencies[term.as_str()] as f64).ln();
+
+            if idf > 0.0 {
+                vector.insert(term.as_str(), (1.0 + (*count as f64).ln()) * idf);
+            }
+        }
+
+        let length = vector.values().map(|x| x * x).sum::<f64>().sqrt();
+
+        if length > 0.0 {
+            vector.values_mut().for_each(|x| *x /= length);
+        }
+
+        vectors.push(vector);
+    }
+
+    return vectors;
+}
+
+fn cosine(a: &HashMap<&str, f64>, b: &HashMap<&str, f64>) -> f64 {
+    let (small, large) = if a.len() < b.len() { (a, b) } else { (b, a) };
+
+    return small
+        .iter()
+        .filter_map(|(term, x)| large.get(term).map(|y| x * y))
+        .sum();
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+
+    if union == 0 {
+        return 0.0;
+    }
+
+    return a.intersection(b).count() as f64 / union as f64;
+}
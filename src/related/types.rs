@@ -0,0 +1,35 @@
+use std::collections::{HashMap, HashSet};
+
+/// Options to configure how related blogs are found. Each other blog is scored
+/// by the tags and keywords it shares with a blog, and by how similar their
+/// text is, and the best scoring blogs are related
+pub struct RelatedOptions {
+    /// The most related blogs for each blog. Default is 3
+    pub count: usize,
+    /// How much sharing tags counts towards the score. Default is 1.0
+    pub tag_weight: f64,
+    /// How much sharing keywords counts towards the score. Default is 0.5
+    pub keyword_weight: f64,
+    /// How much similar text counts towards the score, using TF-IDF. Default
+    /// is 1.0
+    pub text_weight: f64,
+}
+
+impl Default for RelatedOptions {
+    fn default() -> Self {
+        Self {
+            count: 3,
+            tag_weight: 1.0,
+            keyword_weight: 0.5,
+            text_weight: 1.0,
+        }
+    }
+}
+
+// Everything about a single blog which is used to score how related it is
+pub(crate) struct Profile {
+    pub tags: HashSet<String>,
+    pub keywords: HashSet<String>,
+    /// The number of times each term is found in the text
+    pub counts: HashMap<String, usize>,
+}
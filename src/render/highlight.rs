@@ -0,0 +1,156 @@
+use std::sync::OnceLock;
+
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{Theme, ThemeSet},
+    html::{
+        css_for_theme_with_class_style, styled_line_to_highlighted_html, ClassStyle,
+        ClassedHTMLGenerator, IncludeBackground,
+    },
+    parsing::{SyntaxReference, SyntaxSet},
+    util::LinesWithEndings,
+};
+
+use crate::common::{decode_html, BlogError};
+
+use super::types::{HighlightOptions, HighlightStyle};
+
+// Every class is prefixed, so it can't clash with the classes of the site
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+
+// Loading these is slow, so it is only done once
+static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+
+/// The stylesheet for the theme in the `HighlightOptions`. Only needed when
+/// the style is `Classes` - serve this alongside your blog
+pub fn theme_css(options: &HighlightOptions) -> Result<String, BlogError> {
+    let theme = get_theme(&options.theme)?;
+
+    return match css_for_theme_with_class_style(theme, CLASS_STYLE) {
+        Ok(x) => Ok(x),
+        Err(y) => Err(BlogError::Highlight(y.to_string())),
+    };
+}
+
+/// Highlights every fenced code block with a language the highlighter knows.
+/// Any other code block is left as it is
+pub fn highlight(html: &str, options: &HighlightOptions) -> Result<String, BlogError> {
+    const OPEN: &str = "<pre><code class=\"language-";
+    const CLOSE: &str = "</code></pre>";
+
+    let syntaxes = SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines);
+    let theme = get_theme(&options.theme)?;
+
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find(OPEN) {
+        let language_start = start + OPEN.len();
+
+        let language_end = match rest[language_start..].find("\">") {
+            Some(x) => language_start + x,
+            None => break,
+        };
+
+        let code_start = language_end + 2;
+
+        let code_end = match rest[code_start..].find(CLOSE) {
+            Some(x) => code_start + x,
+            None => break,
+        };
+
+        let language = &rest[language_start..language_end];
+        let block_end = code_end + CLOSE.len();
+
+        out.push_str(&rest[..start]);
+
+        match syntaxes.find_syntax_by_token(language) {
+            Some(syntax) => {
+                let code = decode_html(&rest[code_start..code_end]);
+
+                let (pre, highlighted) = match options.style {
+                    HighlightStyle::Inline => (
+                        format!("<pre style=\"{}\">", background(theme)),
+                        highlight_inline(&code, syntax, syntaxes, theme)?,
+                    ),
+                    HighlightStyle::Classes => (
+                        "<pre class=\"hl-code\">".to_string(),
+                        highlight_classes(&code, syntax, syntaxes)?,
+                    ),
+                };
+
+                out.push_str(&pre);
+                out.push_str(&format!("<code class=\"language-{}\">", language));
+                out.push_str(&highlighted);
+                out.push_str(CLOSE);
+            }
+            None => out.push_str(&rest[start..block_end]),
+        }
+
+        rest = &rest[block_end..];
+    }
+
+    out.push_str(rest);
+
+    return Ok(out);
+}
+
+fn highlight_inline(
+    code: &str,
+    syntax: &SyntaxReference,
+    syntaxes: &SyntaxSet,
+    theme: &Theme,
+) -> Result<String, BlogError> {
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    let mut html = String::new();
+
+    for line in LinesWithEndings::from(code) {
+        let regions = match highlighter.highlight_line(line, syntaxes) {
+            Ok(x) => x,
+            Err(y) => return Err(BlogError::Highlight(y.to_string())),
+        };
+
+        match styled_line_to_highlighted_html(&regions, IncludeBackground::No) {
+            Ok(x) => html.push_str(&x),
+            Err(y) => return Err(BlogError::Highlight(y.to_string())),
+        }
+    }
+
+    return Ok(html);
+}
+
+fn highlight_classes(
+    code: &str,
+    syntax: &SyntaxReference,
+    syntaxes: &SyntaxSet,
+) -> Result<String, BlogError> {
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(syntax, syntaxes, CLASS_STYLE);
+
+    for line in LinesWithEndings::from(code) {
+        if let Err(y) = generator.parse_html_for_line_which_includes_newline(line) {
+            return Err(BlogError::Highlight(y.to_string()));
+        }
+    }
+
+    return Ok(generator.finalize());
+}
+
+fn get_theme(name: &str) -> Result<&'static Theme, BlogError> {
+    let themes = THEMES.get_or_init(ThemeSet::load_defaults);
+
+    return match themes.themes.get(name) {
+        Some(x) => Ok(x),
+        None => Err(BlogError::Highlight(format!(
+            "there is no theme called `{}`",
+            name
+        ))),
+    };
+}
+
+fn background(theme: &Theme) -> String {
+    return match theme.settings.background {
+        Some(x) => format!("background-color:#{:02x}{:02x}{:02x};", x.r, x.g, x.b),
+        None => String::new(),
+    };
+}
@@ -0,0 +1,13 @@
+#[cfg(feature = "highlight")]
+mod highlight;
+mod sanitize;
+mod types;
+#[cfg(feature = "highlight")]
+pub use highlight::theme_css;
+#[cfg(feature = "highlight")]
+pub use types::{HighlightOptions, HighlightStyle};
+pub use types::{HtmlSafety, MarkdownDialect, RenderOptions, SanitizeOptions};
+
+#[cfg(feature = "highlight")]
+pub(crate) use highlight::highlight;
+pub(crate) use sanitize::sanitize;
@@ -0,0 +1,84 @@
+use std::collections::{HashMap, HashSet};
+
+use super::types::SanitizeOptions;
+
+/// Cleans the HTML so only the tags, attributes and URL schemes in the
+/// `SanitizeOptions` are kept
+pub fn sanitize(html: &str, options: &SanitizeOptions) -> String {
+    let mut tag_attributes: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for (tag, attributes) in &options.tag_attributes {
+        tag_attributes.insert(tag, to_str(attributes));
+    }
+
+    let allows_code_class = options.generic_attributes.contains("class")
+        || match options.tag_attributes.get("code") {
+            Some(x) => x.contains("class"),
+            None => false,
+        };
+
+    // The language of a code block is always kept, as it is needed to
+    // highlight the code
+    if !allows_code_class {
+        tag_attributes.entry("code").or_default().insert("class");
+    }
+
+    // ammonia always removes `script` and `style` along with their content,
+    // and panics if they are also allowed
+    let mut tags = to_str(&options.tags);
+    tags.remove("script");
+    tags.remove("style");
+
+    let mut builder = ammonia::Builder::default();
+
+    builder
+        .tags(tags)
+        .tag_attributes(tag_attributes)
+        .generic_attributes(to_str(&options.generic_attributes))
+        .url_schemes(to_str(&options.url_schemes));
+
+    // ammonia adds `rel` to links itself, and panics if it is also allowed
+    let allows_rel = options.generic_attributes.contains("rel")
+        || match options.tag_attributes.get("a") {
+            Some(x) => x.contains("rel"),
+            None => false,
+        };
+
+    if allows_rel {
+        builder.link_rel(None);
+    }
+
+    if !allows_code_class {
+        builder.attribute_filter(|element, attribute, value| {
+            if element == "code" && attribute == "class" && !value.starts_with("language-") {
+                return None;
+            }
+            return Some(value.into());
+        });
+    }
+
+    return builder.clean(html).to_string();
+}
+
+fn to_str(set: &HashSet<String>) -> HashSet<&str> {
+    return set.iter().map(|x| x.as_str()).collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sanitize;
+    use crate::render::SanitizeOptions;
+
+    #[test]
+    fn script_and_style_are_never_allowed() {
+        let mut options = SanitizeOptions::default();
+        options.tags.insert("script".to_string());
+        options.tags.insert("style".to_string());
+
+        let html = sanitize(
+            "<p>Hi</p><script>alert(1)</script><style>p {}</style>",
+            &options,
+        );
+
+        assert_eq!(html, "<p>Hi</p>");
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::{HashMap, HashSet};
+
+use markdown::{CompileOptions, Options, ParseOptions};
+use serde::{Deserialize, Serialize};
+
+/// Options to configure how the markdown of every blog is rendered into HTML
+#[derive(Default)]
+pub struct RenderOptions {
+    /// Which flavour of markdown to render. The default is `CommonMark`
+    pub dialect: MarkdownDialect,
+    /// How much to trust the HTML in the blogs. The default is `Trusted`.
+    /// Individual blogs can make this stricter with the `safety` field of
+    /// their metadata, but never less strict
+    pub safety: HtmlSafety,
+    /// The tags, attributes and URL schemes which are kept when the safety is
+    /// `Sanitized`
+    pub sanitize: SanitizeOptions,
+    /// How fenced code blocks are highlighted. The default is `None`, leaving
+    /// them for a highlighter in the browser
+    #[cfg(feature = "highlight")]
+    pub highlight: Option<HighlightOptions>,
+}
+
+/// The flavour of markdown the blogs are written in
+#[derive(Default)]
+pub enum MarkdownDialect {
+    /// Plain CommonMark
+    #[default]
+    CommonMark,
+    /// GitHub flavoured markdown, which adds tables, footnotes, strikethrough,
+    /// task lists and autolinks
+    Gfm,
+    /// Any combination of the `markdown` crate's `ParseOptions` and
+    /// `CompileOptions`, for instance GFM with math
+    ///
+    /// ```rust,ignore
+    /// MarkdownDialect::Custom(Box::new(|| Options {
+    ///     parse: ParseOptions {
+    ///         constructs: Constructs {
+    ///             math_flow: true,
+    ///             math_text: true,
+    ///             ..Constructs::gfm()
+    ///         },
+    ///         ..ParseOptions::gfm()
+    ///     },
+    ///     compile: CompileOptions::gfm(),
+    /// }))
+    /// ```
+    ///
+    /// Unlike the other dialects, when the safety is `Trusted` raw HTML and
+    /// dangerous links are only allowed if the `CompileOptions` allow them
+    Custom(Box<dyn Fn() -> Options + Send + Sync>),
+}
+
+/// How much to trust the HTML in a blog
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum HtmlSafety {
+    /// Raw HTML and links of any kind, including `javascript:`, are rendered
+    /// as they are. Fine when you wrote every blog yourself
+    #[default]
+    Trusted,
+    /// Raw HTML is escaped and dangerous links such as `javascript:` are
+    /// removed
+    Safe,
+    /// Raw HTML is allowed, but the rendered HTML is cleaned so only the tags,
+    /// attributes and URL schemes in the `SanitizeOptions` are kept
+    Sanitized,
+}
+
+impl HtmlSafety {
+    // From `Trusted`, the least strict, to `Safe`, the most
+    fn strictness(&self) -> usize {
+        return match self {
+            HtmlSafety::Trusted => 0,
+            HtmlSafety::Sanitized => 1,
+            HtmlSafety::Safe => 2,
+        };
+    }
+}
+
+/// The allowlist used when the safety is `Sanitized`. The default keeps the
+/// tags and attributes in common use for text, tables and images, plus `id`
+/// so the heading and footnote links still work
+pub struct SanitizeOptions {
+    /// Tags which are kept. Any other tag is removed, keeping its content.
+    /// `script` and `style` are always removed along with their content, even
+    /// if they are listed here
+    pub tags: HashSet<String>,
+    /// Attributes which are kept on a particular tag, by tag name
+    pub tag_attributes: HashMap<String, HashSet<String>>,
+    /// Attributes which are kept on every tag
+    pub generic_attributes: HashSet<String>,
+    /// URL schemes which are kept in links. Relative links are always kept
+    pub url_schemes: HashSet<String>,
+}
+
+impl Default for SanitizeOptions {
+    fn default() -> Self {
+        let builder = ammonia::Builder::default();
+
+        let mut generic_attributes = to_owned(builder.clone_generic_attributes());
+        generic_attributes.insert("id".to_string());
+
+        let mut tag_attributes = HashMap::new();
+        for (tag, attributes) in builder.clone_tag_attributes() {
+            tag_attributes.insert(tag.to_string(), to_owned(attributes));
+        }
+
+        Self {
+            tags: to_owned(builder.clone_tags()),
+            tag_attributes: tag_attributes,
+            generic_attributes: generic_attributes,
+            url_schemes: to_owned(builder.clone_url_schemes()),
+        }
+    }
+}
+
+/// Options to configure how fenced code blocks are highlighted
+#[cfg(feature = "highlight")]
+pub struct HighlightOptions {
+    /// Whether the colours are inline styles or classes. The default is
+    /// `Inline`
+    pub style: HighlightStyle,
+    /// The name of the theme, one of `base16-ocean.dark`, `base16-eighties.dark`,
+    /// `base16-mocha.dark`, `base16-ocean.light`, `InspiredGitHub`,
+    /// `Solarized (dark)` or `Solarized (light)`. The default is `InspiredGitHub`
+    pub theme: String,
+}
+
+#[cfg(feature = "highlight")]
+impl Default for HighlightOptions {
+    fn default() -> Self {
+        Self {
+            style: HighlightStyle::Inline,
+            theme: "InspiredGitHub".to_string(),
+        }
+    }
+}
+
+/// How the colours of highlighted code are written into the HTML
+#[cfg(feature = "highlight")]
+pub enum HighlightStyle {
+    /// Every token has a `style` attribute with its colour, so no stylesheet is
+    /// needed
+    Inline,
+    /// Every token has classes prefixed with `hl-`. Serve the stylesheet from
+    /// `theme_css` alongside the blog
+    Classes,
+}
+
+fn to_owned(set: HashSet<&str>) -> HashSet<String> {
+    return set.into_iter().map(|x| x.to_string()).collect();
+}
+
+impl RenderOptions {
+    /// The `markdown` options for a blog. `safety` is the blog's own safety,
+    /// which is used if it is stricter than the safety in these options
+    pub(crate) fn markdown_options(&self, safety: Option<HtmlSafety>) -> Options {
+        let mut options = match &self.dialect {
+            MarkdownDialect::CommonMark => Options {
+                parse: ParseOptions::default(),
+                compile: CompileOptions {
+                    allow_dangerous_html: true,
+                    allow_dangerous_protocol: true,
+                    ..CompileOptions::default()
+                },
+            },
+            MarkdownDialect::Gfm => Options {
+                parse: ParseOptions::gfm(),
+                compile: CompileOptions {
+                    allow_dangerous_html: true,
+                    allow_dangerous_protocol: true,
+                    ..CompileOptions::gfm()
+                },
+            },
+            MarkdownDialect::Custom(x) => x(),
+        };
+
+        match self.get_safety(safety) {
+            HtmlSafety::Trusted => {}
+            HtmlSafety::Safe => {
+                options.compile.allow_dangerous_html = false;
+                options.compile.allow_dangerous_protocol = false;
+            }
+            // The sanitizer decides what is kept
+            HtmlSafety::Sanitized => {
+                options.compile.allow_dangerous_html = true;
+                options.compile.allow_dangerous_protocol = true;
+            }
+        }
+
+        return options;
+    }
+
+    // A blog can only make its own safety stricter, so a guest author can't
+    // mark their blog as trusted
+    pub(crate) fn get_safety(&self, safety: Option<HtmlSafety>) -> HtmlSafety {
+        return match safety {
+            Some(x) if x.strictness() > self.safety.strictness() => x,
+            _ => self.safety,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HtmlSafety, RenderOptions};
+
+    #[test]
+    fn blog_can_only_tighten_safety() {
+        let mut options = RenderOptions {
+            safety: HtmlSafety::Sanitized,
+            ..RenderOptions::default()
+        };
+
+        assert_eq!(options.get_safety(None), HtmlSafety::Sanitized);
+        assert_eq!(
+            options.get_safety(Some(HtmlSafety::Trusted)),
+            HtmlSafety::Sanitized
+        );
+        assert_eq!(options.get_safety(Some(HtmlSafety::Safe)), HtmlSafety::Safe);
+
+        options.safety = HtmlSafety::Trusted;
+        assert_eq!(
+            options.get_safety(Some(HtmlSafety::Sanitized)),
+            HtmlSafety::Sanitized
+        );
+
+        options.safety = HtmlSafety::Safe;
+        assert_eq!(
+            options.get_safety(Some(HtmlSafety::Trusted)),
+            HtmlSafety::Safe
+        );
+        assert_eq!(
+            options.get_safety(Some(HtmlSafety::Sanitized)),
+            HtmlSafety::Safe
+        );
+    }
+}
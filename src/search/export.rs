@@ -0,0 +1,83 @@
+use crate::{common::BlogError, sitemap::SitemapOptions, types::Blog};
+
+use super::{
+    index::{html_text, terms},
+    types::{ExportDocument, ExportManifest, SearchExport, SearchExportOptions, SearchFile},
+};
+
+// The version of the format of `search.json` when it lists the shards
+const EXPORT_VERSION: usize = 1;
+
+/// Exports a search index of the blogs, which should be newest first
+pub fn export_documents<T: Blog>(
+    entries: &[T],
+    export_options: &SearchExportOptions,
+    sitemap_options: &SitemapOptions,
+) -> Result<SearchExport, BlogError> {
+    let mut documents = vec![];
+
+    for entry in entries {
+        let text = if export_options.include_text {
+            Some(terms(&html_text(&entry.get_html())).join(" "))
+        } else {
+            None
+        };
+
+        documents.push(ExportDocument {
+            id: entry.get_full_slug(),
+            url: format!(
+                "/{}/{}",
+                sitemap_options.blog_root_slug,
+                entry.get_full_slug()
+            ),
+            title: entry.get_title(),
+            date: entry.get_date_listed(),
+            tags: entry.get_tags(),
+            preview: entry.get_preview(),
+            text: text,
+        });
+    }
+
+    let shard_size = match export_options.shard_size {
+        Some(x) if x > 0 => x,
+        _ => {
+            let file = SearchFile {
+                name: format!("{}.json", export_options.file_name),
+                json: to_json(&documents)?,
+            };
+
+            return Ok(SearchExport { files: vec![file] });
+        }
+    };
+
+    let mut shards = vec![];
+
+    for (i, chunk) in documents.chunks(shard_size).enumerate() {
+        shards.push(SearchFile {
+            name: format!("{}-{}.json", export_options.file_name, i + 1),
+            json: to_json(chunk)?,
+        });
+    }
+
+    let manifest = ExportManifest {
+        version: EXPORT_VERSION,
+        count: documents.len(),
+        shards: shards.iter().map(|x| x.name.clone()).collect(),
+    };
+
+    let mut files = vec![SearchFile {
+        name: format!("{}.json", export_options.file_name),
+        json: to_json(&manifest)?,
+    }];
+
+    files.extend(shards);
+
+    return Ok(SearchExport { files: files });
+}
+
+fn to_json<T: serde::Serialize + ?Sized>(value: &T) -> Result<String, BlogError> {
+    return match serde_json::to_string(value) {
+        Ok(x) => Ok(x),
+        Err(y) => Err(BlogError::Json(y)),
+    };
+}
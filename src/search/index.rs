@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    common::{decode_html, is_cjk},
+    types::Blog,
+};
+
+use super::types::{Posting, SearchDocument, SearchIndex, SearchOptions};
+
+// Each field starts this many positions after the end of the last one, so a
+// phrase never matches across two fields
+const FIELD_GAP: usize = 2;
+
+/// Indexes the title, tags, keywords and text of every blog. `texts` is the
+/// plain text of each blog, in the same order as `entries`
+pub fn build_index<T: Blog>(
+    entries: &[T],
+    texts: &[String],
+    options: &SearchOptions,
+) -> SearchIndex {
+    let mut documents = vec![];
+    let mut terms: BTreeMap<String, Vec<Posting>> = BTreeMap::new();
+    let mut total_length = 0.0;
+
+    for (doc, (entry, text)) in entries.iter().zip(texts).enumerate() {
+        let title = entry.get_title();
+        let tags = entry.get_tags();
+        let keywords = entry.get_keywords().unwrap_or_default();
+
+        let mut fields: Vec<(&str, f64)> = vec![(&title, options.title_weight)];
+        fields.extend(tags.iter().map(|x| (x.as_str(), options.tag_weight)));
+        fields.extend(keywords.iter().map(|x| (x.as_str(), options.tag_weight)));
+        fields.push((text, 1.0));
+
+        let mut postings: BTreeMap<String, Posting> = BTreeMap::new();
+        let mut position = 0;
+        let mut length = 0.0;
+
+        for (field, weight) in fields {
+            for (start, end) in tokens(field) {
+                let posting = postings
+                    .entry(field[start..end].to_lowercase())
+                    .or_insert(Posting {
+                        doc: doc,
+                        weight: 0.0,
+                        positions: vec![],
+                    });
+
+                posting.weight += weight;
+                posting.positions.push(position);
+
+                position += 1;
+                length += weight;
+            }
+
+            position += FIELD_GAP;
+        }
+
+        for (term, posting) in postings {
+            terms.entry(term).or_default().push(posting);
+        }
+
+        total_length += length;
+
+        documents.push(SearchDocument {
+            slug: entry.get_full_slug(),
+            title: title,
+            text: text.clone(),
+            length: length,
+        });
+    }
+
+    let average_length = match documents.len() {
+        0 => 0.0,
+        x => total_length / x as f64,
+    };
+
+    return SearchIndex {
+        documents: documents,
+        terms: terms,
+        average_length: average_length,
+    };
+}
+
+/// The plain text of rendered HTML, with the blocks separated by spaces
+pub fn html_text(html: &str) -> String {
+    let dom = match tl::parse(html, tl::ParserOptions::default()) {
+        Ok(x) => x,
+        Err(_) => return String::new(),
+    };
+
+    let parser = dom.parser();
+
+    let mut blocks = vec![];
+
+    for handle in dom.children() {
+        let node = match handle.get(parser) {
+            Some(x) => x,
+            None => continue,
+        };
+
+        if node.as_comment().is_some() {
+            continue;
+        }
+
+        blocks.push(decode_html(&node.inner_text(parser)));
+    }
+
+    let text = blocks.join(" ");
+
+    return text.split_whitespace().collect::<Vec<&str>>().join(" ");
+}
+
+/// The byte range of every word in the text. Chinese and Japanese are written
+/// without spaces, so each of their characters is a word of its own
+pub fn tokens(text: &str) -> Vec<(usize, usize)> {
+    let mut tokens = vec![];
+    let mut start = None;
+
+    for (i, c) in text.char_indices() {
+        if is_cjk(c) {
+            if let Some(x) = start.take() {
+                tokens.push((x, i));
+            }
+            tokens.push((i, i + c.len_utf8()));
+        } else if c.is_alphanumeric() {
+            if start.is_none() {
+                start = Some(i);
+            }
+        } else if let Some(x) = start.take() {
+            tokens.push((x, i));
+        }
+    }
+
+    if let Some(x) = start {
+        tokens.push((x, text.len()));
+    }
+
+    return tokens;
+}
+
+/// Every word in the text, in lowercase
+pub fn terms(text: &str) -> Vec<String> {
+    return tokens(text)
+        .into_iter()
+        .map(|(start, end)| text[start..end].to_lowercase())
+        .collect();
+}
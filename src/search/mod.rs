@@ -0,0 +1,10 @@
+mod export;
+mod index;
+mod query;
+mod types;
+pub use types::{
+    SearchExport, SearchExportOptions, SearchFile, SearchIndex, SearchOptions, SearchResult,
+};
+
+pub(crate) use export::export_documents;
+pub(crate) use index::{build_index, html_text, terms};
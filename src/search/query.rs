@@ -0,0 +1,426 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::common::escape_html;
+
+use super::{
+    index::{terms, tokens},
+    types::{Posting, SearchIndex, SearchOptions, SearchResult},
+};
+
+// A single part of a query, which every matching blog must contain
+enum QueryPart {
+    Term(String),
+    Prefix(String),
+    Phrase(Vec<String>),
+}
+
+// How well a single blog matches, and the terms which matched for the snippet
+struct Match {
+    score: f64,
+    terms: HashSet<String>,
+}
+
+pub fn search(index: &SearchIndex, query: &str, options: &SearchOptions) -> Vec<SearchResult> {
+    let mut matches: Option<HashMap<usize, Match>> = None;
+
+    for part in parse_query(query) {
+        let part_matches = match_part(index, &part, options);
+
+        matches = match matches {
+            None => Some(part_matches),
+            Some(x) => Some(intersect(x, part_matches)),
+        };
+    }
+
+    let mut matches: Vec<(usize, Match)> = match matches {
+        Some(x) => x.into_iter().collect(),
+        None => return vec![],
+    };
+
+    // The blogs are indexed newest first, so equal matches are newest first
+    matches.sort_by(|a, b| b.1.score.total_cmp(&a.1.score).then(a.0.cmp(&b.0)));
+
+    let mut results = vec![];
+
+    for (doc, found) in matches {
+        let document = &index.documents[doc];
+
+        results.push(SearchResult {
+            slug: document.slug.clone(),
+            title: document.title.clone(),
+            score: found.score,
+            snippet: snippet(&document.text, &found.terms, options),
+        });
+    }
+
+    return results;
+}
+
+// Words in quotes are a phrase, and a word ending with `*` is a prefix. A word
+// such as `e-mail` is split into more than one term, so is also a phrase
+fn parse_query(query: &str) -> Vec<QueryPart> {
+    let mut parts = vec![];
+
+    for (i, piece) in query.split('"').enumerate() {
+        // Every other piece is inside quotes
+        if i % 2 == 1 {
+            parts.extend(query_part(terms(piece), false));
+            continue;
+        }
+
+        for word in piece.split_whitespace() {
+            parts.extend(query_part(terms(word), word.ends_with('*')));
+        }
+    }
+
+    return parts;
+}
+
+fn query_part(mut terms: Vec<String>, prefix: bool) -> Option<QueryPart> {
+    return match terms.len() {
+        0 => None,
+        1 if prefix => terms.pop().map(QueryPart::Prefix),
+        1 => terms.pop().map(QueryPart::Term),
+        _ => Some(QueryPart::Phrase(terms)),
+    };
+}
+
+fn match_part(
+    index: &SearchIndex,
+    part: &QueryPart,
+    options: &SearchOptions,
+) -> HashMap<usize, Match> {
+    let mut matches: HashMap<usize, Match> = HashMap::new();
+
+    match part {
+        QueryPart::Term(term) => {
+            if let Some(postings) = index.terms.get(term) {
+                add_term(index, term, postings, &mut matches, options);
+            }
+        }
+        QueryPart::Prefix(prefix) => {
+            let found = index
+                .terms
+                .range(prefix.clone()..)
+                .take_while(|(x, _)| x.starts_with(prefix.as_str()));
+
+            for (term, postings) in found {
+                add_term(index, term, postings, &mut matches, options);
+            }
+        }
+        QueryPart::Phrase(terms) => {
+            let counts = phrase_counts(index, terms);
+
+            let idf = idf(index, counts.len());
+
+            for (doc, count) in counts {
+                matches.insert(
+                    doc,
+                    Match {
+                        score: bm25(index, idf, count as f64, doc, options),
+                        terms: terms.iter().cloned().collect(),
+                    },
+                );
+            }
+        }
+    };
+
+    return matches;
+}
+
+fn add_term(
+    index: &SearchIndex,
+    term: &str,
+    postings: &[Posting],
+    matches: &mut HashMap<usize, Match>,
+    options: &SearchOptions,
+) {
+    let idf = idf(index, postings.len());
+
+    for posting in postings {
+        let found = matches.entry(posting.doc).or_insert(Match {
+            score: 0.0,
+            terms: HashSet::new(),
+        });
+
+        found.score += bm25(index, idf, posting.weight, posting.doc, options);
+        found.terms.insert(term.to_string());
+    }
+}
+
+// The number of times the phrase is found in each blog which has it
+fn phrase_counts(index: &SearchIndex, terms: &[String]) -> HashMap<usize, usize> {
+    let mut counts = HashMap::new();
+
+    let mut postings = vec![];
+
+    for term in terms {
+        match index.terms.get(term) {
+            Some(x) => postings.push(x),
+            None => return counts,
+        };
+    }
+
+    for first in postings[0] {
+        let mut positions = vec![];
+
+        for other in &postings[1..] {
+            match other.binary_search_by_key(&first.doc, |x| x.doc) {
+                Ok(x) => positions.push(&other[x].positions),
+                Err(_) => break,
+            };
+        }
+
+        if positions.len() < postings.len() - 1 {
+            continue;
+        }
+
+        let count = first
+            .positions
+            .iter()
+            .filter(|&&start| {
+                positions
+                    .iter()
+                    .enumerate()
+                    .all(|(i, x)| x.binary_search(&(start + i + 1)).is_ok())
+            })
+            .count();
+
+        if count > 0 {
+            counts.insert(first.doc, count);
+        }
+    }
+
+    return counts;
+}
+
+fn intersect(a: HashMap<usize, Match>, mut b: HashMap<usize, Match>) -> HashMap<usize, Match> {
+    let mut both = HashMap::new();
+
+    for (doc, mut found) in a {
+        let other = match b.remove(&doc) {
+            Some(x) => x,
+            None => continue,
+        };
+
+        found.score += other.score;
+        found.terms.extend(other.terms);
+
+        both.insert(doc, found);
+    }
+
+    return both;
+}
+
+fn idf(index: &SearchIndex, matching: usize) -> f64 {
+    let total = index.documents.len() as f64;
+    let matching = matching as f64;
+
+    return (1.0 + (total - matching + 0.5) / (matching + 0.5)).ln();
+}
+
+fn bm25(index: &SearchIndex, idf: f64, frequency: f64, doc: usize, options: &SearchOptions) -> f64 {
+    let ratio = if index.average_length > 0.0 {
+        index.documents[doc].length / index.average_length
+    } else {
+        1.0
+    };
+
+    let norm = options.k1 * (1.0 - options.b + options.b * ratio);
+
+    return idf * frequency * (options.k1 + 1.0) / (frequency + norm);
+}
+
+// Around `snippet_chars` of the text starting a little before the first match,
+// with every matching word highlighted
+fn snippet(text: &str, terms: &HashSet<String>, options: &SearchOptions) -> String {
+    let mut spans: Vec<(usize, usize)> = vec![];
+
+    for (start, end) in tokens(text) {
+        if !terms.contains(&text[start..end].to_lowercase()) {
+            continue;
+        }
+
+        // Matches with nothing between them, such as Japanese characters, are
+        // highlighted together
+        match spans.last_mut() {
+            Some(x) if x.1 == start => x.1 = end,
+            _ => spans.push((start, end)),
+        };
+    }
+
+    let start = match spans.first() {
+        Some((x, _)) => snippet_start(text, *x, options.snippet_chars / 4),
+        None => 0,
+    };
+
+    let end = snippet_end(text, start, options.snippet_chars);
+
+    let mut snippet = String::new();
+
+    if start > 0 {
+        snippet.push('…');
+    }
+
+    let mut last = start;
+
+    for (span_start, span_end) in spans {
+        if span_start < start || span_end > end {
+            continue;
+        }
+
+        snippet.push_str(&escape_html(&text[last..span_start]));
+        snippet.push_str(&options.highlight_start);
+        snippet.push_str(&escape_html(&text[span_start..span_end]));
+        snippet.push_str(&options.highlight_end);
+
+        last = span_end;
+    }
+
+    snippet.push_str(&escape_html(&text[last..end]));
+
+    if end < text.len() {
+        snippet.push('…');
+    }
+
+    return snippet;
+}
+
+// Up to `context` characters before the first match, at the start of a word
+fn snippet_start(text: &str, first: usize, context: usize) -> usize {
+    let before = &text[..first];
+
+    let start = match before.char_indices().rev().nth(context) {
+        Some((x, _)) => x,
+        None => return 0,
+    };
+
+    return match before[start..].find(char::is_whitespace) {
+        Some(x) => start + x + 1,
+        // A language without spaces
+        None => start,
+    };
+}
+
+// Up to `num_chars` characters after the start, at the end of a word
+fn snippet_end(text: &str, start: usize, num_chars: usize) -> usize {
+    let cut = match text[start..].char_indices().nth(num_chars) {
+        Some((x, _)) => start + x,
+        None => return text.len(),
+    };
+
+    if text[cut..].starts_with(char::is_whitespace) {
+        return cut;
+    }
+
+    return match text[start..cut].rfind(char::is_whitespace) {
+        Some(x) => start + x,
+        None => cut,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use serde_json::json;
+
+    use super::{search, snippet};
+    use crate::{
+        search::{index::build_index, SearchIndex, SearchOptions},
+        testing::low_entry,
+    };
+
+    fn index(blogs: &[(&str, &str)]) -> SearchIndex {
+        let mut entries = vec![];
+        let mut texts = vec![];
+
+        for (i, (title, text)) in blogs.iter().enumerate() {
+            entries.push(low_entry(json!({
+                "title": title,
+                "slug": format!("blog-{}", i),
+            })));
+            texts.push(text.to_string());
+        }
+
+        return build_index(&entries, &texts, &SearchOptions::default());
+    }
+
+    fn titles(index: &SearchIndex, query: &str) -> Vec<String> {
+        return search(index, query, &SearchOptions::default())
+            .into_iter()
+            .map(|x| x.title)
+            .collect();
+    }
+
+    #[test]
+    fn every_term_must_match() {
+        let index = index(&[
+            ("Rust", "Writing a blog in Rust"),
+            ("Python", "Writing a blog in Python"),
+        ]);
+
+        assert_eq!(titles(&index, "rust"), vec!["Rust"]);
+        assert_eq!(titles(&index, "BLOG rust"), vec!["Rust"]);
+        assert_eq!(titles(&index, "blog").len(), 2);
+        assert!(titles(&index, "rust python").is_empty());
+        assert!(titles(&index, "").is_empty());
+    }
+
+    #[test]
+    fn phrases_match_in_order() {
+        let index = index(&[
+            ("Static", "How to build a static site"),
+            ("Backwards", "The site is static"),
+        ]);
+
+        assert_eq!(titles(&index, "\"static site\""), vec!["Static"]);
+        assert_eq!(titles(&index, "static site").len(), 2);
+    }
+
+    #[test]
+    fn prefixes_match_the_start_of_words() {
+        let index = index(&[
+            ("Fast", "Optimising the build"),
+            ("Slow", "Nothing to see here"),
+        ]);
+
+        assert_eq!(titles(&index, "optim*"), vec!["Fast"]);
+        assert!(titles(&index, "timis*").is_empty());
+    }
+
+    #[test]
+    fn snippets_cut_on_char_boundaries() {
+        let options = SearchOptions {
+            snippet_chars: 12,
+            ..SearchOptions::default()
+        };
+
+        let mut terms = HashSet::new();
+        terms.insert("bier".to_string());
+
+        let text = "Grüße aus München, wo das Bier sehr gut schmeckt";
+        let found = snippet(text, &terms, &options);
+
+        assert!(found.starts_with('…'));
+        assert!(found.ends_with('…'));
+        assert!(found.contains("<mark>Bier</mark>"));
+
+        let mut terms = HashSet::new();
+        terms.insert("日".to_string());
+        terms.insert("本".to_string());
+
+        let found = snippet("これは日本語のブログです", &terms, &options);
+        assert!(found.contains("<mark>日本</mark>"));
+    }
+
+    #[test]
+    fn snippets_are_escaped() {
+        let mut terms = HashSet::new();
+        terms.insert("script".to_string());
+
+        let found = snippet("<script>", &terms, &SearchOptions::default());
+
+        assert_eq!(found, "&lt;<mark>script</mark>&gt;");
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::query::search;
+
+/// An in memory full text search index of the blogs. Build one by setting
+/// `search` in the `BlogOptions`, and query it with `search`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchIndex {
+    pub(crate) documents: Vec<SearchDocument>,
+    /// Each term to the blogs it is found in, in the same order as `documents`
+    pub(crate) terms: BTreeMap<String, Vec<Posting>>,
+    pub(crate) average_length: f64,
+}
+
+impl SearchIndex {
+    /// Searches the blogs for `query`, best match first. Every word of the
+    /// query must be found in a blog for it to match. Put words in quotes to
+    /// match them as a phrase e.g. `"static site"`, and end a word with `*` to
+    /// match every word starting with it e.g. `optim*`
+    pub fn search(&self, query: &str, options: &SearchOptions) -> Vec<SearchResult> {
+        return search(self, query, options);
+    }
+}
+
+// A single blog in the index
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct SearchDocument {
+    pub slug: String,
+    pub title: String,
+    /// The plain text of the rendered blog, for the snippets
+    pub text: String,
+    /// The number of terms, weighted by the field they are in
+    pub length: f64,
+}
+
+// Where a term is found in a single blog
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct Posting {
+    /// The index of the blog in `documents`
+    pub doc: usize,
+    /// The number of times the term is found, weighted by the field it is in
+    pub weight: f64,
+    /// The position of every occurrence, for matching phrases
+    pub positions: Vec<usize>,
+}
+
+/// A blog which matches a search
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchResult {
+    /// The full slug of the blog e.g. `2024-03-19/my-blog`. Use this with
+    /// `hash` to find the blog itself
+    pub slug: String,
+    /// Title of the blog post
+    pub title: String,
+    /// How well the blog matches, using BM25. Higher is better
+    pub score: f64,
+    /// A short piece of the text of the blog around the first match, with every
+    /// matching word highlighted. This is HTML, so it does not need escaping
+    pub snippet: String,
+}
+
+/// Options to configure how blogs are indexed and searched. The weights are
+/// used when the index is built, so come from the `search` of the
+/// `BlogOptions`, and the rest are used when it is searched
+pub struct SearchOptions {
+    /// How much more a word in the title counts than a word in the text.
+    /// Default is 3.0
+    pub title_weight: f64,
+    /// How much more a word in the tags or keywords counts than a word in the
+    /// text. Default is 2.0
+    pub tag_weight: f64,
+    /// The BM25 `k1` parameter, which controls how quickly more occurrences of
+    /// a word stop making a blog a better match. Default is 1.2
+    pub k1: f64,
+    /// The BM25 `b` parameter, from 0.0 to 1.0, which controls how much longer
+    /// blogs are penalised. Default is 0.75
+    pub b: f64,
+    /// The rough number of characters in a snippet. Default is 160
+    pub snippet_chars: usize,
+    /// Added before every matching word in a snippet. Default is `<mark>`
+    pub highlight_start: String,
+    /// Added after every matching word in a snippet. Default is `</mark>`
+    pub highlight_end: String,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            title_weight: 3.0,
+            tag_weight: 2.0,
+            k1: 1.2,
+            b: 0.75,
+            snippet_chars: 160,
+            highlight_start: "<mark>".to_string(),
+            highlight_end: "</mark>".to_string(),
+        }
+    }
+}
+
+/// A search index for searching in the browser, such as on a statically
+/// deployed blog. Write each of the `files` next to each other
+///
+/// The first file is always `search.json`, named from the `file_name` of the
+/// `SearchExportOptions`. Without sharding this is a JSON array with a
+/// document for every blog, newest first
+///
+/// ```json
+/// [
+///     {
+///         "id": "2024-03-19/my-blog",
+///         "url": "/blog/2024-03-19/my-blog",
+///         "title": "My Blog",
+///         "date": "2024-03-19",
+///         "tags": ["Rust"],
+///         "preview": "The first few hundred characters of the blog",
+///         "text": "every word of the blog in lowercase"
+///     }
+/// ]
+/// ```
+///
+/// This array can be given as it is to client side search libraries such as
+/// Lunr, MiniSearch or Fuse.js. With sharding, `search.json` instead lists
+/// the shards, which each hold an array of documents as above
+///
+/// ```json
+/// {"version": 1, "count": 25, "shards": ["search-1.json", "search-2.json"]}
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchExport {
+    /// Every file of the search index, in the order they are listed
+    pub files: Vec<SearchFile>,
+}
+
+/// A single file of an exported search index
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchFile {
+    /// The name of the file e.g. `search.json`
+    pub name: String,
+    /// The JSON to write to the file
+    pub json: String,
+}
+
+// A single blog in an exported search index
+#[derive(Serialize)]
+pub(crate) struct ExportDocument {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub date: NaiveDate,
+    pub tags: Vec<String>,
+    pub preview: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+// The first file of a sharded search index
+#[derive(Serialize)]
+pub(crate) struct ExportManifest {
+    pub version: usize,
+    pub count: usize,
+    pub shards: Vec<String>,
+}
+
+/// Options to configure how a search index is exported
+pub struct SearchExportOptions {
+    /// The name of the files, without `.json`. The default is `search`, which
+    /// means the first file is `search.json` and the shards are
+    /// `search-1.json`, `search-2.json` and so on
+    pub file_name: String,
+    /// The number of blogs in each shard. The default is `None`, meaning every
+    /// blog is in `search.json` itself
+    pub shard_size: Option<usize>,
+    /// Whether to include the text of every blog, as lowercase words separated
+    /// by spaces. Without it only the titles, tags and previews can be
+    /// searched, but the files are much smaller. The default is `true`
+    pub include_text: bool,
+}
+
+impl Default for SearchExportOptions {
+    fn default() -> Self {
+        Self {
+            file_name: "search".to_string(),
+            shard_size: None,
+            include_text: true,
+        }
+    }
+}
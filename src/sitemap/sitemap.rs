@@ -1,16 +1,24 @@
-use std::{io::Cursor, path::Path};
+use std::{
+    io::{Cursor, Write},
+    path::Path,
+};
 
-use xml::{reader::XmlEvent as ReaderXmlEvent, writer::XmlEvent, EmitterConfig, EventReader};
+use xml::{
+    reader::XmlEvent as ReaderXmlEvent, writer::XmlEvent, EmitterConfig, EventReader, EventWriter,
+};
 
 use crate::{
+    archive::{archive_url, build_archive},
     common::{parse_blogs, BlogError},
     low::LowBlogEntry,
-    types::Blog,
+    pagination::{page_url, total_pages},
+    tags::{encode_slug, Tag},
+    types::{Blog, BlogOptions},
 };
 
 use super::types::SitemapOptions;
 
-const DATE_FORMAT: &'static str = "%d-%m-%Y";
+const DATE_FORMAT: &str = "%d-%m-%Y";
 
 /// Use this function in `low` mode to generate a sitemap
 ///
@@ -19,19 +27,22 @@ const DATE_FORMAT: &'static str = "%d-%m-%Y";
 /// - `blog_root`: Path to the root of the blog e.g. `files/blog`
 /// - `url_base`: URL of the website e.g. `www.example.com`
 /// - `options`: `SitemapOptions` for configuration
+/// - `blog_options`: `BlogOptions`, used to decide which blogs are included
 pub fn create_sitemap<T: AsRef<Path>>(
     blog_root: T,
     url_base: &String,
     options: &SitemapOptions,
+    blog_options: &BlogOptions,
 ) -> Result<String, BlogError> {
-    let (entries, tags): (Vec<LowBlogEntry>, Vec<String>) = parse_blogs(blog_root, None, None)?;
+    let (entries, tags): (Vec<LowBlogEntry>, Vec<Tag>) =
+        parse_blogs(blog_root, None, None, blog_options)?;
 
     return create_sitemap_inner(&entries, Some(&tags), url_base, options);
 }
 
 pub fn create_sitemap_inner<T: Blog>(
     entries: &Vec<T>,
-    maybe_tags: Option<&Vec<String>>,
+    maybe_tags: Option<&Vec<Tag>>,
     url_base: &String,
     options: &SitemapOptions,
 ) -> Result<String, BlogError> {
@@ -49,13 +60,14 @@ pub fn create_sitemap_inner<T: Blog>(
 
     let default_priority = options.default_priority;
 
+    // The pages other than the blogs are always taken to be modified today
+    let today = chrono::offset::Utc::now()
+        .date_naive()
+        .format(DATE_FORMAT)
+        .to_string();
+
     // Blog pages
     for blog in entries {
-        writer.write(XmlEvent::start_element("url")).unwrap();
-
-        // Location
-        writer.write(XmlEvent::start_element("loc")).unwrap();
-
         let loc = format!(
             "{}/{}/{}",
             url_base,
@@ -63,80 +75,92 @@ pub fn create_sitemap_inner<T: Blog>(
             blog.get_full_slug()
         );
 
-        writer.write(XmlEvent::characters(&loc)).unwrap();
-        writer.write(XmlEvent::end_element()).unwrap();
-
-        // Last Modified
-        writer.write(XmlEvent::start_element("lastmod")).unwrap();
-
         let lastmod = match blog.get_last_modified() {
             Some(x) => x.format(DATE_FORMAT).to_string(),
             None => blog.get_date_listed().format(DATE_FORMAT).to_string(),
         };
 
-        writer.write(XmlEvent::characters(&lastmod)).unwrap();
-        writer.write(XmlEvent::end_element()).unwrap();
-
-        // Priority
-
-        writer.write(XmlEvent::start_element("priority")).unwrap();
-
         let priority = match blog.get_priority() {
             None => default_priority,
             Some(x) => {
-                if x > 1.0 || x < 0.0 {
+                if !(0.0..=1.0).contains(&x) {
                     panic!("Priority must be between 0.0 and 1.0, got `{}`", x);
                 }
 
                 x
             }
         };
-        writer
-            .write(XmlEvent::characters(&format!("{}", priority)))
-            .unwrap();
-        writer.write(XmlEvent::end_element()).unwrap();
 
-        writer.write(XmlEvent::end_element()).unwrap(); // Finish <url>
+        write_url(&mut writer, &loc, &lastmod, priority);
     }
 
     // Tag pages
-    if options.include_tags && maybe_tags.is_some() {
-        let tags = maybe_tags.unwrap();
-        let current_time = chrono::offset::Utc::now();
-        let lastmod = current_time.date_naive().format(&DATE_FORMAT).to_string();
-
+    if let (true, Some(tags)) = (options.include_tags, maybe_tags) {
         for tag in tags {
-            writer.write(XmlEvent::start_element("url")).unwrap();
-
-            // Location
-            writer.write(XmlEvent::start_element("loc")).unwrap();
-
-            let loc = format!("{}/{}/{}", url_base, options.tag_root_slug, tag);
+            let loc = format!(
+                "{}/{}/{}",
+                url_base,
+                options.tag_root_slug,
+                encode_slug(&tag.slug)
+            );
+
+            write_url(&mut writer, &loc, &today, default_priority);
+        }
+    }
 
-            writer.write(XmlEvent::characters(&loc)).unwrap();
-            writer.write(XmlEvent::end_element()).unwrap();
+    // Archive pages
+    if options.include_archive {
+        for year in build_archive(entries) {
+            let loc = format!(
+                "{}/{}",
+                url_base,
+                archive_url(&options.archive_root_slug, year.year, None)
+            );
+
+            write_url(&mut writer, &loc, &today, default_priority);
+
+            for month in year.months {
+                let loc = format!(
+                    "{}/{}",
+                    url_base,
+                    archive_url(&options.archive_root_slug, month.year, Some(month.month))
+                );
+
+                write_url(&mut writer, &loc, &today, default_priority);
+            }
+        }
+    }
 
-            // Last Modified
-            writer.write(XmlEvent::start_element("lastmod")).unwrap();
+    // Index pages
+    if let Some(pagination) = &options.pagination {
+        let mut roots = vec![(options.blog_root_slug.clone(), entries.len())];
 
-            writer.write(XmlEvent::characters(&lastmod)).unwrap();
-            writer.write(XmlEvent::end_element()).unwrap();
+        if let (true, Some(tags)) = (options.include_tags, maybe_tags) {
+            for tag in tags {
+                let count = entries
+                    .iter()
+                    .filter(|x| x.get_tag_slugs().contains(&tag.slug))
+                    .count();
 
-            // Priority
+                let root_slug = format!("{}/{}", options.tag_root_slug, encode_slug(&tag.slug));
 
-            writer.write(XmlEvent::start_element("priority")).unwrap();
+                roots.push((root_slug, count));
+            }
+        }
 
-            writer
-                .write(XmlEvent::characters(&format!("{}", default_priority)))
-                .unwrap();
-            writer.write(XmlEvent::end_element()).unwrap();
+        for (root_slug, count) in roots {
+            // The first page is the index itself, so only the pages after it
+            // are added
+            for page in 2..=total_pages(count, pagination) {
+                let loc = format!("{}/{}", url_base, page_url(&root_slug, page, pagination));
 
-            writer.write(XmlEvent::end_element()).unwrap(); // Finish <url>
+                write_url(&mut writer, &loc, &today, default_priority);
+            }
         }
     }
 
     if let Some(sitemap_base) = &options.sitemap_base {
-        let parser = EventReader::from_str(&sitemap_base);
+        let parser = EventReader::from_str(sitemap_base);
 
         for e in parser {
             match e {
@@ -172,3 +196,24 @@ pub fn create_sitemap_inner<T: Blog>(
 
     return Ok(String::from_utf8(buffer.into_inner()).unwrap());
 }
+
+// Writes a single `<url>` with its location, last modified date and priority
+fn write_url<W: Write>(writer: &mut EventWriter<W>, loc: &str, lastmod: &str, priority: f64) {
+    writer.write(XmlEvent::start_element("url")).unwrap();
+
+    writer.write(XmlEvent::start_element("loc")).unwrap();
+    writer.write(XmlEvent::characters(loc)).unwrap();
+    writer.write(XmlEvent::end_element()).unwrap();
+
+    writer.write(XmlEvent::start_element("lastmod")).unwrap();
+    writer.write(XmlEvent::characters(lastmod)).unwrap();
+    writer.write(XmlEvent::end_element()).unwrap();
+
+    writer.write(XmlEvent::start_element("priority")).unwrap();
+    writer
+        .write(XmlEvent::characters(&format!("{}", priority)))
+        .unwrap();
+    writer.write(XmlEvent::end_element()).unwrap();
+
+    writer.write(XmlEvent::end_element()).unwrap(); // Finish <url>
+}
@@ -1,3 +1,5 @@
+use crate::pagination::PaginationOptions;
+
 /// Options to configure how the sitemap is generated
 ///
 /// `blog-tools` can generate a sitemap for all of the blogs and tags,
@@ -25,11 +27,26 @@ pub struct SitemapOptions {
     /// For example, if you had a tag called `science` then the
     /// URL would be `www.example.com/blog/tag/science`.
     pub tag_root_slug: String,
+    /// Whether to include archive pages in the sitemap. Set this to `true` if
+    /// your website has a page for every year and every month with blogs
+    pub include_archive: bool,
+    /// This represents the location of the archive in the URL. The default is
+    /// `blog/archive`, if you set `include_archive` to `true`. If
+    /// `include_archive` is `false` (default behaviour), then this is ignored.
+    /// For example, the blogs of March 2024 would be at
+    /// `www.example.com/blog/archive/2024/03`, and the blogs of the whole year
+    /// at `www.example.com/blog/archive/2024`
+    pub archive_root_slug: String,
     /// Optional `String` representation of an XML sitemap. This function will
     /// automatically merge the records of this sitemap into the sitemap it
     /// generates. Useful if you have a bunch of pages which are not part of the
     /// blog that you'd like in the sitemap
     pub sitemap_base: Option<String>,
+    /// Optional `PaginationOptions`. When set, the index pages after the first
+    /// are included in the sitemap e.g. `www.example.com/blog/page/2`, and the
+    /// pages of each tag too if `include_tags` is `true`. The default is
+    /// `None`, meaning no index pages are included
+    pub pagination: Option<PaginationOptions>,
 }
 
 impl Default for SitemapOptions {
@@ -39,7 +56,10 @@ impl Default for SitemapOptions {
             include_tags: false,
             blog_root_slug: "blog".to_string(), // TODO: Maybe move this value into a constant?
             tag_root_slug: "blog/tag".to_string(),
+            include_archive: false,
+            archive_root_slug: "blog/archive".to_string(),
             sitemap_base: None,
+            pagination: None,
         }
     }
 }
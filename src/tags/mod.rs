@@ -0,0 +1,6 @@
+mod tags;
+mod types;
+pub use tags::{read_tag_aliases, tag_cloud, tag_slug};
+pub use types::{Tag, TagCloudOptions, TagCount, TagOptions, TagSlugStyle, TagWeight};
+
+pub(crate) use tags::{encode_slug, normalise_tag, normalise_tags, sorted_counts, tag_index};
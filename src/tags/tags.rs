@@ -0,0 +1,276 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::Path,
+};
+
+use crate::{common::BlogError, types::Blog};
+
+use super::types::{Tag, TagCloudOptions, TagCount, TagOptions, TagSlugStyle, TagWeight};
+
+/// Turns a tag into its slug, using the `slug_style` of the `TagOptions`.
+/// Useful to find the slug of a tag in `low` mode
+pub fn tag_slug(tag: &str, options: &TagOptions) -> String {
+    return match &options.slug_style {
+        TagSlugStyle::Hyphenated => hyphenate(tag),
+        TagSlugStyle::Custom(x) => x(tag),
+    };
+}
+
+/// Reads the aliases of the tags from a JSON, YAML or TOML file, depending on
+/// its extension. The file maps the name of each tag to the other ways it is
+/// written, for instance in YAML
+///
+/// ```yaml
+/// Machine Learning: [ML, machine-learning]
+/// Rust: [rustlang]
+/// ```
+pub fn read_tag_aliases<T: AsRef<Path>>(
+    path: T,
+) -> Result<BTreeMap<String, Vec<String>>, BlogError> {
+    let path = path.as_ref();
+
+    let text = match fs::read_to_string(path) {
+        Ok(x) => x,
+        Err(y) => return Err(BlogError::File(y)),
+    };
+
+    let extension = match path.extension() {
+        Some(x) => x.to_string_lossy().to_lowercase(),
+        None => String::new(),
+    };
+
+    return match extension.as_str() {
+        "json" => match serde_json::from_str(&text) {
+            Ok(x) => Ok(x),
+            Err(y) => Err(BlogError::Json(y)),
+        },
+        "yaml" | "yml" => match serde_yaml::from_str(&text) {
+            Ok(x) => Ok(x),
+            Err(y) => Err(BlogError::TagsFile(y.to_string())),
+        },
+        "toml" => match toml::from_str(&text) {
+            Ok(x) => Ok(x),
+            Err(y) => Err(BlogError::TagsFile(y.message().trim().replace('\n', ", "))),
+        },
+        _ => Err(BlogError::ImproperFileName(path.display().to_string())),
+    };
+}
+
+/// Weights every tag for a tag cloud, sorted alphabetically. Weights are
+/// spread on a logarithmic scale, so a handful of very popular tags don't
+/// leave every other tag at the lowest weight
+pub fn tag_cloud(counts: &[TagCount], options: &TagCloudOptions) -> Vec<TagWeight> {
+    let levels = options.levels.max(1);
+
+    let min = counts.iter().map(|x| x.count).min().unwrap_or(0).max(1) as f64;
+    let max = counts.iter().map(|x| x.count).max().unwrap_or(0).max(1) as f64;
+
+    let mut weights: Vec<TagWeight> = counts
+        .iter()
+        .map(|x| {
+            let weight = if max > min {
+                let scale = ((x.count.max(1) as f64).ln() - min.ln()) / (max.ln() - min.ln());
+                1 + (scale * (levels - 1) as f64).round() as usize
+            } else {
+                1
+            };
+
+            TagWeight {
+                name: x.name.clone(),
+                slug: x.slug.clone(),
+                count: x.count,
+                weight: weight,
+            }
+        })
+        .collect();
+
+    weights.sort_by(|a, b| a.name.cmp(&b.name));
+
+    return weights;
+}
+
+/// Normalises the tags of a blog, returning the name and the slug of each.
+/// Aliases are replaced by the name of their tag, tags with the same slug are
+/// only kept once, and tags with an empty slug are left out
+pub fn normalise_tags(tags: &[String], options: &TagOptions) -> (Vec<String>, Vec<String>) {
+    let mut names = vec![];
+    let mut slugs = vec![];
+
+    for tag in tags {
+        let mut slug = tag_slug(tag, options);
+        let mut name = tag.trim().to_string();
+
+        for (alias_name, aliases) in &options.aliases {
+            let is_alias = aliases.iter().any(|x| tag_slug(x, options) == slug);
+
+            if is_alias || tag_slug(alias_name, options) == slug {
+                name = alias_name.clone();
+                slug = tag_slug(alias_name, options);
+                break;
+            }
+        }
+
+        if slug.is_empty() || slugs.contains(&slug) {
+            continue;
+        }
+
+        names.push(name);
+        slugs.push(slug);
+    }
+
+    return (names, slugs);
+}
+
+/// Normalises a single tag, such as one given to find the blogs with it. The
+/// slug is empty if the tag has no letters or numbers
+pub fn normalise_tag(tag: &str, options: &TagOptions) -> Tag {
+    let (mut names, mut slugs) = normalise_tags(&[tag.to_string()], options);
+
+    return match (names.pop(), slugs.pop()) {
+        (Some(name), Some(slug)) => Tag {
+            name: name,
+            slug: slug,
+        },
+        _ => Tag {
+            name: tag.to_string(),
+            slug: String::new(),
+        },
+    };
+}
+
+/// Maps the slug of every tag to the full slugs of the blogs with it, in the
+/// same order as `entries`
+pub fn tag_index<T: Blog>(entries: &[T]) -> HashMap<String, Vec<String>> {
+    let mut index: HashMap<String, Vec<String>> = HashMap::new();
+
+    for entry in entries {
+        for tag_slug in entry.get_tag_slugs() {
+            index
+                .entry(tag_slug)
+                .or_default()
+                .push(entry.get_full_slug());
+        }
+    }
+
+    return index;
+}
+
+/// Sorts the tags from most to least used, and alphabetically when the counts
+/// are the same
+pub fn sorted_counts(mut counts: Vec<TagCount>) -> Vec<TagCount> {
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+
+    return counts;
+}
+
+/// Percent encodes a tag slug for use as a single segment of a URL, such as
+/// in the sitemap, the feeds and the pages of a tag. `/` is encoded too, so a
+/// slug can't add segments to the URL
+pub fn encode_slug(slug: &str) -> String {
+    let mut encoded = String::new();
+
+    for byte in slug.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    return encoded;
+}
+
+fn hyphenate(tag: &str) -> String {
+    let mut slug = String::new();
+
+    for c in tag.chars() {
+        let word = match c {
+            '+' => "-plus-",
+            '#' => "-sharp-",
+            '&' => "-and-",
+            _ if c.is_alphanumeric() => {
+                slug.extend(c.to_lowercase());
+                continue;
+            }
+            _ => "-",
+        };
+
+        slug.push_str(word);
+    }
+
+    // Runs of `-` become a single `-`, and none are left at either end
+    return slug
+        .split('-')
+        .filter(|x| !x.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{encode_slug, hyphenate, normalise_tag, normalise_tags, TagOptions};
+
+    fn strings(x: &[&str]) -> Vec<String> {
+        return x.iter().map(|y| y.to_string()).collect();
+    }
+
+    #[test]
+    fn hyphenates_tags() {
+        assert_eq!(hyphenate("Machine Learning"), "machine-learning");
+        assert_eq!(hyphenate("  machine--learning! "), "machine-learning");
+        assert_eq!(hyphenate("C++"), "c-plus-plus");
+        assert_eq!(hyphenate("C#"), "c-sharp");
+        assert_eq!(hyphenate("R&D"), "r-and-d");
+        assert_eq!(hyphenate("Ärger"), "ärger");
+        assert_eq!(hyphenate("!!!"), "");
+    }
+
+    #[test]
+    fn folds_aliases_and_duplicates() {
+        let mut aliases = BTreeMap::new();
+        aliases.insert("Machine Learning".to_string(), strings(&["ML"]));
+
+        let options = TagOptions {
+            aliases: aliases,
+            ..TagOptions::default()
+        };
+
+        let (names, slugs) = normalise_tags(
+            &strings(&["ml", "machine-learning", "Rust", "rust", "?"]),
+            &options,
+        );
+
+        assert_eq!(names, strings(&["Machine Learning", "Rust"]));
+        assert_eq!(slugs, strings(&["machine-learning", "rust"]));
+
+        assert_eq!(normalise_tag("ML", &options).slug, "machine-learning");
+        assert_eq!(normalise_tag("?", &options).slug, "");
+    }
+
+    #[test]
+    fn encodes_slugs() {
+        assert_eq!(encode_slug("machine-learning"), "machine-learning");
+        assert_eq!(encode_slug("ärger"), "%C3%A4rger");
+        assert_eq!(encode_slug("a b"), "a%20b");
+        assert_eq!(encode_slug("a/../b"), "a%2F..%2Fb");
+    }
+
+    #[test]
+    fn overlapping_aliases_resolve_alphabetically() {
+        let mut aliases = BTreeMap::new();
+        aliases.insert("Zig".to_string(), strings(&["systems"]));
+        aliases.insert("C".to_string(), strings(&["systems"]));
+        aliases.insert("Rust".to_string(), strings(&["systems"]));
+
+        let options = TagOptions {
+            aliases: aliases,
+            ..TagOptions::default()
+        };
+
+        assert_eq!(normalise_tag("Systems", &options).name, "C");
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A tag, with the name it is shown with and the slug used in its URLs
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Tag {
+    /// The name of the tag, as it is shown on a page
+    pub name: String,
+    /// The URL slug of the tag e.g. `machine-learning`
+    pub slug: String,
+}
+
+/// A tag, and the number of blogs with it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TagCount {
+    /// The name of the tag
+    pub name: String,
+    /// The URL slug of the tag
+    pub slug: String,
+    /// The number of blogs with the tag
+    pub count: usize,
+}
+
+/// A tag weighted for a tag cloud, so more popular tags can be shown larger
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TagWeight {
+    /// The name of the tag
+    pub name: String,
+    /// The URL slug of the tag
+    pub slug: String,
+    /// The number of blogs with the tag
+    pub count: usize,
+    /// From 1 for the least used tags up to `levels` for the most used. Useful
+    /// as part of a class name e.g. `tag-3`
+    pub weight: usize,
+}
+
+/// Options to configure how tags are weighted in a tag cloud
+pub struct TagCloudOptions {
+    /// The number of different weights. Default is 5
+    pub levels: usize,
+}
+
+impl Default for TagCloudOptions {
+    fn default() -> Self {
+        Self { levels: 5 }
+    }
+}
+
+/// Options to configure how the tags of every blog are normalised. Tags with
+/// the same slug are the same tag, so `Machine Learning` and
+/// `machine-learning` are one tag
+#[derive(Default)]
+pub struct TagOptions {
+    /// How a tag is turned into its slug. The default is `Hyphenated`
+    pub slug_style: TagSlugStyle,
+    /// The name of a tag to the other ways it is written, for instance
+    /// `Machine Learning` to `ML`. Every blog tagged with an alias is tagged
+    /// with the name instead. Aliases are compared by their slug, so there is
+    /// no need to list every spelling. If a tag matches more than one name,
+    /// the first name in alphabetical order is used. Read these from a file
+    /// with `read_tag_aliases`
+    pub aliases: BTreeMap<String, Vec<String>>,
+}
+
+/// How a tag is turned into its slug
+#[derive(Default)]
+pub enum TagSlugStyle {
+    /// Lowercased, with every run of spaces and punctuation replaced by a
+    /// single `-`. `+`, `#` and `&` become `plus`, `sharp` and `and`, so
+    /// `C++` is `c-plus-plus` and `C#` is `c-sharp`
+    #[default]
+    Hyphenated,
+    /// Any function from the tag to its slug. The slug should be safe to use
+    /// in a URL
+    ///
+    /// ```rust,ignore
+    /// TagSlugStyle::Custom(Box::new(|tag| tag.to_lowercase().replace(' ', "_")))
+    /// ```
+    Custom(Box<dyn Fn(&str) -> String + Send + Sync>),
+}
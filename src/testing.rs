@@ -0,0 +1,98 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    common::BlogJson,
+    low::LowBlogEntry,
+    tags::{normalise_tags, TagOptions},
+};
+
+static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+
+// A folder of blogs in the temp directory, removed when dropped so it is
+// cleaned up even when a test fails
+pub struct TempBlog {
+    pub path: PathBuf,
+}
+
+impl TempBlog {
+    pub fn new() -> Self {
+        let path = env::temp_dir().join(format!(
+            "blog-tools-{}-{}",
+            process::id(),
+            NEXT_DIR.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        fs::create_dir_all(&path).unwrap();
+
+        return TempBlog { path: path };
+    }
+
+    // Writes a file relative to the root, creating any folders it is in
+    pub fn write(&self, name: &str, contents: &str) -> PathBuf {
+        let path = self.path.join(name);
+
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, contents).unwrap();
+
+        return path;
+    }
+}
+
+impl AsRef<Path> for TempBlog {
+    fn as_ref(&self) -> &Path {
+        return &self.path;
+    }
+}
+
+impl Drop for TempBlog {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+// The metadata of a blog. `fields` are added to, or replace, the fields of a
+// minimal blog
+pub fn blog_json(fields: Value) -> BlogJson {
+    let mut value = json!({
+        "title": "A Blog",
+        "date": "2024-01-02",
+        "desc": "A blog",
+        "slug": "a-blog",
+        "tags": [],
+    });
+
+    if let (Value::Object(x), Value::Object(y)) = (&mut value, fields) {
+        x.extend(y);
+    }
+
+    let mut json: BlogJson = serde_json::from_value(value).unwrap();
+
+    let (names, slugs) = normalise_tags(&json.tags, &TagOptions::default());
+    json.tags = names;
+    json.tag_slugs = slugs;
+
+    return json;
+}
+
+pub fn low_entry(fields: Value) -> LowBlogEntry {
+    return low_entry_with(fields, "", "");
+}
+
+pub fn low_entry_with(fields: Value, html: &str, preview: &str) -> LowBlogEntry {
+    return LowBlogEntry::new(
+        blog_json(fields),
+        html.to_string(),
+        None,
+        preview.to_string(),
+        None,
+        0,
+        0,
+    );
+}
@@ -0,0 +1,6 @@
+mod toc;
+mod types;
+pub use toc::{generate_toc, get_toc, slugify, toc_to_html};
+pub use types::{TocEntry, TocOptions};
+
+pub(crate) use toc::{add_heading_ids, heading_sources};
@@ -0,0 +1,317 @@
+use std::collections::HashSet;
+
+use markdown::mdast::Node;
+
+use crate::common::{decode_html, escape_html};
+
+use super::types::{TocEntry, TocOptions};
+
+/// The built in table of contents generator, using the default `TocOptions`.
+/// Pass this as the `toc_generation_func` to get a table of contents whose
+/// links match the `id`s of the rendered headings
+///
+/// ```rust,ignore
+/// get_high_blog(PathBuf::from(BLOG_ROOT), Some(&generate_toc), None, ...)
+/// ```
+///
+/// For other options, wrap `get_toc` and `toc_to_html` in a closure
+///
+/// ```rust,ignore
+/// let toc_func = |node: &Node| {
+///     toc_to_html(&get_toc(node, &TocOptions { min_depth: 2, max_depth: 3 }))
+/// };
+/// ```
+pub fn generate_toc(node: &Node) -> String {
+    return toc_to_html(&get_toc(node, &TocOptions::default()));
+}
+
+/// Finds the headings in the markdown, nesting each heading beneath the
+/// heading before it with a lower level. Only headings between `min_depth` and
+/// `max_depth` are included
+pub fn get_toc(node: &Node, options: &TocOptions) -> Vec<TocEntry> {
+    let mut headings = vec![];
+    collect_headings(node, &mut headings);
+
+    let mut seen = HashSet::new();
+    let mut entries = vec![];
+
+    for (level, text) in headings {
+        // Every heading gets an id, even those left out of the table of
+        // contents, so the ids match the rendered HTML
+        let id = unique_id(&text, &mut seen);
+
+        if level < options.min_depth || level > options.max_depth {
+            continue;
+        }
+
+        insert_entry(
+            &mut entries,
+            TocEntry {
+                level: level,
+                text: text,
+                id: id,
+                children: vec![],
+            },
+        );
+    }
+
+    return entries;
+}
+
+/// Renders a table of contents as nested `<ul>` lists of links to each
+/// heading
+pub fn toc_to_html(entries: &[TocEntry]) -> String {
+    if entries.is_empty() {
+        return String::new();
+    }
+
+    let mut html = String::from("<ul>");
+
+    for entry in entries {
+        html.push_str(&format!(
+            "<li><a href=\"#{}\">{}</a>",
+            entry.id,
+            escape_html(&entry.text)
+        ));
+        html.push_str(&toc_to_html(&entry.children));
+        html.push_str("</li>");
+    }
+
+    html.push_str("</ul>");
+
+    return html;
+}
+
+/// Converts heading text into an `id`, in the same way as GitHub. Letters are
+/// lowercased, whitespace becomes `-` and punctuation other than `-` and `_`
+/// is removed
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+
+    for c in text.trim().chars() {
+        if c.is_alphanumeric() || c == '-' || c == '_' {
+            slug.extend(c.to_lowercase());
+        } else if c.is_whitespace() {
+            slug.push('-');
+        }
+    }
+
+    return slug;
+}
+
+/// Gives every heading in the rendered HTML an `id` matching the table of
+/// contents. Headings which already have attributes are left alone.
+///
+/// `from_markdown` is whether each heading, in order, came from the markdown
+/// rather than raw HTML, as found by `heading_sources`. Headings written in
+/// raw HTML are left alone too, as the table of contents leaves them out. Any
+/// heading past the end is taken to be from the markdown
+pub(crate) fn add_heading_ids(html: &str, from_markdown: &[bool]) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut seen = HashSet::new();
+    let mut rest = html;
+    let mut index = 0;
+
+    while let Some(start) = rest.find("<h") {
+        let (level, plain) = match heading_tag(&rest[start..]) {
+            Some(x) => x,
+            None => {
+                out.push_str(&rest[..start + 2]);
+                rest = &rest[start + 2..];
+                continue;
+            }
+        };
+
+        let markdown = from_markdown.get(index) != Some(&false);
+        index += 1;
+
+        if !plain || !markdown {
+            out.push_str(&rest[..start + 3]);
+            rest = &rest[start + 3..];
+            continue;
+        }
+
+        let close = format!("</h{}>", level);
+        let inner_start = start + 4;
+
+        let inner_end = match rest[inner_start..].find(&close) {
+            Some(x) => inner_start + x,
+            None => break,
+        };
+
+        let inner = &rest[inner_start..inner_end];
+        let id = unique_id(&decode_html(&strip_tags(inner)), &mut seen);
+
+        out.push_str(&rest[..start]);
+        out.push_str(&format!("<h{} id=\"{}\">", level, id));
+        out.push_str(inner);
+        out.push_str(&close);
+
+        rest = &rest[inner_end + close.len()..];
+    }
+
+    out.push_str(rest);
+
+    return out;
+}
+
+/// Whether each heading in the rendered HTML, in order, comes from the
+/// markdown rather than raw HTML. Only useful when raw HTML is rendered as it
+/// is, rather than escaped
+pub(crate) fn heading_sources(node: &Node) -> Vec<bool> {
+    let mut sources = vec![];
+    collect_sources(node, &mut sources);
+
+    return sources;
+}
+
+fn collect_sources(node: &Node, sources: &mut Vec<bool>) {
+    match node {
+        // Anything inside the heading is skipped along with it
+        Node::Heading(_) => sources.push(true),
+        Node::Html(x) => {
+            let mut rest = x.value.as_str();
+
+            while let Some(start) = rest.find("<h") {
+                if heading_tag(&rest[start..]).is_some() {
+                    sources.push(false);
+                }
+                rest = &rest[start + 2..];
+            }
+        }
+        _ => {
+            if let Some(children) = node.children() {
+                for child in children {
+                    collect_sources(child, sources);
+                }
+            }
+        }
+    }
+}
+
+// The level of the heading tag at the start of `html`, and whether it is a
+// plain tag with no attributes
+fn heading_tag(html: &str) -> Option<(char, bool)> {
+    let bytes = html.as_bytes();
+
+    let level = match bytes.get(2) {
+        Some(x) if (b'1'..=b'6').contains(x) => *x as char,
+        _ => return None,
+    };
+
+    return match bytes.get(3) {
+        Some(b'>') => Some((level, true)),
+        Some(x) if x.is_ascii_whitespace() || *x == b'/' => Some((level, false)),
+        _ => None,
+    };
+}
+
+fn collect_headings(node: &Node, headings: &mut Vec<(u8, String)>) {
+    if let Node::Heading(x) = node {
+        headings.push((x.depth, node_text(node).trim().to_string()));
+        return;
+    }
+
+    if let Some(children) = node.children() {
+        for child in children {
+            collect_headings(child, headings);
+        }
+    }
+}
+
+// The plain text of a node. Raw HTML is left out, to match the text of the
+// rendered heading once its tags are stripped
+fn node_text(node: &Node) -> String {
+    return match node {
+        Node::Text(x) => x.value.clone(),
+        Node::InlineCode(x) => x.value.clone(),
+        Node::InlineMath(x) => x.value.clone(),
+        Node::Html(_) => String::new(),
+        _ => match node.children() {
+            Some(x) => x.iter().map(node_text).collect(),
+            None => String::new(),
+        },
+    };
+}
+
+// Nests the entry beneath the last entry with a lower level
+fn insert_entry(siblings: &mut Vec<TocEntry>, entry: TocEntry) {
+    match siblings.last_mut() {
+        Some(x) if x.level < entry.level => insert_entry(&mut x.children, entry),
+        _ => siblings.push(entry),
+    }
+}
+
+// Repeated headings get `-1`, `-2` and so on added to their id
+fn unique_id(text: &str, seen: &mut HashSet<String>) -> String {
+    let mut slug = slugify(text);
+
+    if slug.is_empty() {
+        slug = "section".to_string();
+    }
+
+    let mut id = slug.clone();
+    let mut count = 0;
+
+    while seen.contains(&id) {
+        count += 1;
+        id = format!("{}-{}", slug, count);
+    }
+
+    seen.insert(id.clone());
+
+    return id;
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    return text;
+}
+
+#[cfg(test)]
+mod tests {
+    use markdown::{to_mdast, ParseOptions};
+
+    use super::{get_toc, TocOptions};
+    use crate::{common::render_markdown, render::HtmlSafety, BlogOptions};
+
+    #[test]
+    fn raw_html_headings_keep_toc_ids() {
+        let markdown = "<h2>Intro</h2>\n\n## Intro\n\n## Intro\n\n<h3 class=\"x\">Intro</h3>\n";
+
+        let html = render_markdown(markdown, None, &BlogOptions::default()).unwrap();
+
+        let mdast = to_mdast(markdown, &ParseOptions::default()).unwrap();
+        let ids: Vec<String> = get_toc(&mdast, &TocOptions::default())
+            .into_iter()
+            .map(|x| x.id)
+            .collect();
+
+        assert_eq!(ids, vec!["intro", "intro-1"]);
+        assert_eq!(
+            html,
+            "<h2>Intro</h2>\n<h2 id=\"intro\">Intro</h2>\n<h2 id=\"intro-1\">Intro</h2>\n<h3 class=\"x\">Intro</h3>\n"
+        );
+    }
+
+    #[test]
+    fn escaped_html_is_not_a_heading() {
+        let mut blog_options = BlogOptions::default();
+        blog_options.render.safety = HtmlSafety::Safe;
+
+        let html = render_markdown("<h2>Intro</h2>\n\n## Intro\n", None, &blog_options).unwrap();
+
+        assert!(html.contains("<h2 id=\"intro\">Intro</h2>"));
+    }
+}
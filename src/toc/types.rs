@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+/// A single heading in the table of contents, along with every heading
+/// nested beneath it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TocEntry {
+    /// The heading level, from 1 for `#` to 6 for `######`
+    pub level: u8,
+    /// The plain text of the heading
+    pub text: String,
+    /// The `id` of the heading in the rendered HTML, so `#{id}` links to it
+    pub id: String,
+    /// Headings nested beneath this one
+    pub children: Vec<TocEntry>,
+}
+
+/// Options to configure which headings are in the table of contents
+pub struct TocOptions {
+    /// The shallowest heading level to include. Default is 1
+    pub min_depth: u8,
+    /// The deepest heading level to include. Default is 6
+    pub max_depth: u8,
+}
+
+impl Default for TocOptions {
+    fn default() -> Self {
+        Self {
+            min_depth: 1,
+            max_depth: 6,
+        }
+    }
+}
@@ -1,9 +1,19 @@
 use std::path::Path;
 
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime};
 use markdown::mdast::Node;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
 
-use crate::common::BlogError;
+use crate::{
+    common::BlogError,
+    layout::{BlogLayout, DatedLayout},
+    preview::PreviewOptions,
+    related::RelatedOptions,
+    render::RenderOptions,
+    search::SearchOptions,
+    tags::TagOptions,
+};
 
 // TODO: give these lifetimes so we don't need to clone
 /// Primary trait that describes a single blog post. Any struct which derives
@@ -16,8 +26,8 @@ pub trait Blog: Clone {
     /// Parameters
     ///
     /// - `blog`: Path to the root of the blog
-    ///     For this to work, you should have a folder, for instance, `blog` which serves
-    ///     as the root. Within this folder, you must have the following structure
+    ///   For this to work, you should have a folder, for instance, `blog` which serves
+    ///   as the root. Within this folder, you must have the following structure
     ///
     ///     - blog
     ///         - 2023
@@ -27,13 +37,15 @@ pub trait Blog: Clone {
     ///             - (other folders)
     ///
     /// - `toc_generation_func` - A function which parses a blog and generates
-    ///     a table of contents. Optional.
+    ///   a table of contents. Optional.
     /// - `preview_chars` - number of chars to be taken in the preview of the
-    ///     blog. Default is 320.
+    ///   blog. Default is 320.
+    /// - `blog_options` - configures how the blog is rendered
     fn create<T: AsRef<Path>>(
         blog: T,
         toc_generation_func: Option<&dyn Fn(&Node) -> String>,
         preview_chars: Option<usize>,
+        blog_options: &BlogOptions,
     ) -> Result<Self, BlogError>;
     /// Get the blog title
     fn get_title(&self) -> String;
@@ -51,6 +63,8 @@ pub trait Blog: Clone {
     fn get_part_slug(&self) -> String;
     /// Get a list of tags for the blog
     fn get_tags(&self) -> Vec<String>;
+    /// Get the URL slugs of the tags, in the same order as `get_tags`
+    fn get_tag_slugs(&self) -> Vec<String>;
     /// Get the table of contents. Only present if a table of contents funciton
     /// was provided
     fn get_table_of_contents(&self) -> Option<String>;
@@ -65,9 +79,94 @@ pub trait Blog: Clone {
     /// Get the blog preview. This is the first few hundred characters of the blog,
     /// useful for an index page
     fn get_preview(&self) -> String;
+    /// Get the HTML excerpt of the blog. Unlike the preview this keeps links,
+    /// emphasis and the like. Only present if `excerpt_blocks` is set in the
+    /// `PreviewOptions`
+    fn get_excerpt(&self) -> Option<String>;
+    /// Get the number of words in the blog, not counting code blocks
+    fn get_word_count(&self) -> usize;
+    /// Get the estimated reading time in minutes, from the `words_per_minute`
+    /// in the `BlogOptions`
+    fn get_reading_time(&self) -> usize;
     /// Get the last modified date, mostly use for sitemaps. This is not the
     /// original publication date
     fn get_last_modified(&self) -> Option<NaiveDate>;
     /// Get the priority for the sitemap
     fn get_priority(&self) -> Option<f64>;
+    /// Whether the blog is a draft. Drafts are only ever included when
+    /// `include_drafts` is set in the `BlogOptions`
+    fn is_draft(&self) -> bool;
+    /// Get the time the blog is scheduled to be published, in UTC. Until then
+    /// the blog is treated like a draft
+    fn get_publish_at(&self) -> Option<NaiveDateTime>;
+    /// Get any metadata which `blog-tools` does not use itself, such as a
+    /// `cover_image` or `series`. This is also available to templates as
+    /// `extra`
+    fn get_extra(&self) -> Map<String, Value>;
+    /// Get the extra metadata as your own type, for instance
+    ///
+    /// ```rust,ignore
+    /// #[derive(Deserialize)]
+    /// struct Extra {
+    ///     cover_image: Option<String>,
+    ///     series: Option<String>,
+    /// }
+    ///
+    /// let extra: Extra = blog.get_extra_as()?;
+    /// ```
+    fn get_extra_as<E: DeserializeOwned>(&self) -> Result<E, BlogError> {
+        return match serde_json::from_value(Value::Object(self.get_extra())) {
+            Ok(x) => Ok(x),
+            Err(y) => Err(BlogError::Json(y)),
+        };
+    }
+}
+
+/// Options to configure how blogs are found and which are included, shared by
+/// all three modes
+pub struct BlogOptions {
+    /// Whether to include drafts, and blogs whose `publish_at` is still in the
+    /// future. The default is `false`, meaning these blogs are left out of
+    /// everything - the entries, the tags, the sitemap and the feeds. Set this
+    /// to `true` on a local preview server to see unpublished blogs
+    pub include_drafts: bool,
+    /// How the blog is laid out on disc. The default is `DatedLayout`, which
+    /// expects blogs organised by year and then by date
+    pub layout: Box<dyn BlogLayout + Send + Sync>,
+    /// How the markdown is rendered into HTML. The default renders plain
+    /// CommonMark
+    pub render: RenderOptions,
+    /// How the plain text previews are made
+    pub preview: PreviewOptions,
+    /// The reading speed used to estimate the reading time of every blog. The
+    /// default is 200 words per minute
+    pub words_per_minute: usize,
+    /// How the tags of every blog are normalised, such as aliases for tags
+    /// which are written in different ways
+    pub tags: TagOptions,
+    /// Whether `high` and `medium` build a full text search index of the blogs,
+    /// and how. The default is `None`, meaning there is no search index. The
+    /// index holds the text of every blog, so `medium` uses more memory with
+    /// one. `low` never builds a search index
+    pub search: Option<SearchOptions>,
+    /// Whether `high` and `medium` find the related blogs of every blog, and
+    /// how. The default is `None`, meaning no related blogs are found. `low`
+    /// finds the related blogs of a single blog with `get_related_blogs`
+    /// instead
+    pub related: Option<RelatedOptions>,
+}
+
+impl Default for BlogOptions {
+    fn default() -> Self {
+        Self {
+            include_drafts: false,
+            layout: Box::new(DatedLayout),
+            render: RenderOptions::default(),
+            preview: PreviewOptions::default(),
+            words_per_minute: 200,
+            tags: TagOptions::default(),
+            search: None,
+            related: None,
+        }
+    }
 }